@@ -3,14 +3,14 @@ use quote::format_ident;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::{Dot, Star};
-use syn::{Expr, ExprMethodCall, ExprPath, ExprReference, ExprUnary, Path};
-use syn::{Ident, PathSegment, Token, Type};
+use syn::{parse_quote, Expr, ExprMethodCall, ExprPath, ExprReference, ExprUnary, Path};
+use syn::{GenericArgument, Ident, PathArguments, PathSegment, Token, Type};
 
 use super::ty::RedbType;
 
 /// Arguments declared on a named struct field.
 #[derive(FromField, Clone)]
-#[darling(attributes(entry))]
+#[darling(attributes(entry), and_then = "Self::resolve_adapter")]
 pub struct EntryArgs {
     ident: Option<Ident>,
     ty: Type,
@@ -23,8 +23,33 @@ pub struct EntryArgs {
     from: Option<Expr>,
     /// The method to call to resolve into the redb type.
     into: Option<Expr>,
+    /// The index of the variable within the key or value tuple.
+    order: Option<usize>,
+    /// The adapter declaring the redb type and conversion operations.
+    adapter: Option<Path>,
+    /// The function validating the field.
+    validate: Option<Path>,
+    /// Set the field to the current time when the model is inserted.
+    created_at: Option<bool>,
+    /// Set the field to the current time when the model is inserted or updated.
+    updated_at: Option<bool>,
+    /// The field holding the version checked and incremented by updates.
+    version: Option<bool>,
 }
 
+/// Adapters provided by the `redb_model` crate, and the name of the adapter
+/// type within the `redb_model::adapter` module.
+const ADAPTERS: [(&str, &str); 8] = [
+    ("chrono", "Chrono"),
+    ("net", "Net"),
+    ("rust_decimal", "Decimal"),
+    ("std_path", "StdPath"),
+    ("std_time", "StdTime"),
+    ("time", "Time"),
+    ("ulid", "Ulid"),
+    ("uuid", "Uuid"),
+];
+
 #[derive(FromMeta, Clone, PartialEq, Eq)]
 #[darling(rename_all = "lowercase")]
 pub enum EntryPosition {
@@ -33,11 +58,77 @@ pub enum EntryPosition {
 }
 
 impl EntryArgs {
+    /// Declare the redb type and conversion operations of the `adapter`.
+    fn resolve_adapter(mut self) -> darling::Result<Self> {
+        let Some(adapter) = self.adapter.take() else {
+            return Ok(self);
+        };
+        if self.redb_type.is_some() || self.from.is_some() || self.into.is_some() {
+            return Err(darling::Error::custom(
+                "`adapter` cannot be declared with `redb_type`, `from` or `into`",
+            )
+            .with_span(&adapter));
+        }
+
+        // Resolve built-in adapters by name.
+        let adapter: Path = match adapter.get_ident().map(Ident::to_string) {
+            Some(name) if adapter.segments.len() == 1 => {
+                match ADAPTERS.iter().find(|(adapter, _)| *adapter == name) {
+                    Some((_, ty)) => {
+                        let ty = format_ident!("{}", ty, span = adapter.span());
+                        parse_quote!(redb_model::adapter::#ty)
+                    }
+                    None => adapter,
+                }
+            }
+            _ => adapter,
+        };
+
+        let ident = self.ident().clone();
+        let ty = &self.ty;
+        let redb_type: Type =
+            parse_quote!(<#adapter as redb_model::adapter::Adapter<#ty>>::RedbType);
+        self.from = Some(parse_quote!(
+            <#adapter as redb_model::adapter::Adapter<#ty>>::from_redb(#ident)
+        ));
+        self.into = Some(parse_quote!(
+            <#adapter as redb_model::adapter::Adapter<#ty>>::into_redb(#ident)
+        ));
+        self.redb_type = Some(redb_type.into());
+
+        Ok(self)
+    }
+
     /// Get the `VariablePosition`.
     pub fn position(&self) -> &EntryPosition {
         &self.position
     }
 
+    /// Get the index of the variable within the key or value tuple.
+    pub fn order(&self) -> Option<usize> {
+        self.order
+    }
+
+    /// Get the function validating the field.
+    pub fn validate(&self) -> Option<&Path> {
+        self.validate.as_ref()
+    }
+
+    /// Whether the field is set when the model is inserted.
+    pub fn created_at(&self) -> bool {
+        Some(true) == self.created_at
+    }
+
+    /// Whether the field is set when the model is inserted or updated.
+    pub fn updated_at(&self) -> bool {
+        Some(true) == self.updated_at
+    }
+
+    /// Whether the field is the version checked and incremented by updates.
+    pub fn version(&self) -> bool {
+        Some(true) == self.version
+    }
+
     /// The field name within the model.
     pub fn ident(&self) -> &Ident {
         &self.ident.as_ref().expect("Named struct")
@@ -83,6 +174,67 @@ impl EntryArgs {
         }
     }
 
+    /// Declare a borrowed `redb_type` for common owned types, along with the
+    /// conversion operations, if no `redb_type` is declared.
+    pub fn infer_borrowed(&mut self) {
+        if self.redb_type.is_some() {
+            return;
+        }
+
+        let ident = self.ident().clone();
+        let parts = owned_parts(&self.ty);
+        let parts = parts.as_ref().map(|(ty, arg)| (ty.as_str(), *arg));
+        let (redb_type, from, into): (Type, Expr, Expr) = match parts {
+            Some(("String", None)) => (
+                parse_quote!(&str),
+                parse_quote!(#ident.to_owned()),
+                parse_quote!(#ident.as_str()),
+            ),
+            Some(("Vec", Some(arg))) if is_ident(arg, "u8") => (
+                parse_quote!(&[u8]),
+                parse_quote!(#ident.to_owned()),
+                parse_quote!(#ident.as_slice()),
+            ),
+            Some(("Box" | "Rc" | "Arc", Some(arg))) if is_ident(arg, "str") => (
+                parse_quote!(&str),
+                parse_quote!(#ident.into()),
+                parse_quote!(&**#ident),
+            ),
+            Some(("Box" | "Rc" | "Arc", Some(arg))) if is_u8_slice(arg) => (
+                parse_quote!(&[u8]),
+                parse_quote!(#ident.into()),
+                parse_quote!(&**#ident),
+            ),
+            Some(("Cow", Some(arg))) if is_ident(arg, "str") => (
+                parse_quote!(&str),
+                parse_quote!(#ident.to_owned().into()),
+                parse_quote!(&**#ident),
+            ),
+            Some(("Cow", Some(arg))) if is_u8_slice(arg) => (
+                parse_quote!(&[u8]),
+                parse_quote!(#ident.to_owned().into()),
+                parse_quote!(&**#ident),
+            ),
+            Some(("PathBuf", None)) => {
+                let ty = &self.ty;
+                (
+                    parse_quote!(&[u8]),
+                    parse_quote!(
+                        <redb_model::adapter::StdPath as redb_model::adapter::Adapter<#ty>>::from_redb(#ident)
+                    ),
+                    parse_quote!(
+                        <redb_model::adapter::StdPath as redb_model::adapter::Adapter<#ty>>::into_redb(#ident)
+                    ),
+                )
+            }
+            _ => return,
+        };
+
+        self.redb_type = Some(redb_type.into());
+        self.from.get_or_insert(from);
+        self.into.get_or_insert(into);
+    }
+
     /// Type conversion operation **from** the `redb` type.
     pub fn from_op(&self) -> Expr {
         self.from.clone().unwrap_or_else(|| {
@@ -154,3 +306,31 @@ impl EntryArgs {
         })
     }
 }
+
+/// Get the ident of the last path segment of a `Type`, and the first generic
+/// type argument, if any.
+fn owned_parts(ty: &Type) -> Option<(String, Option<&Type>)> {
+    let Type::Path(ty) = ty else {
+        return None;
+    };
+    let segment = ty.path.segments.last()?;
+    let arg = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    };
+
+    Some((segment.ident.to_string(), arg))
+}
+
+/// Whether the `Type` is the given ident.
+fn is_ident(ty: &Type, ident: &str) -> bool {
+    matches!(ty, Type::Path(ty) if ty.path.is_ident(ident))
+}
+
+/// Whether the `Type` is a `[u8]` slice.
+fn is_u8_slice(ty: &Type) -> bool {
+    matches!(ty, Type::Slice(slice) if is_ident(&slice.elem, "u8"))
+}
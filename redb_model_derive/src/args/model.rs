@@ -1,7 +1,7 @@
 use darling::ast::Data;
-use darling::util::Ignored;
+use darling::util::{Ignored, PathList};
 use darling::{FromDeriveInput, FromMeta};
-use syn::Ident;
+use syn::{Ident, Path, Visibility};
 
 use super::EntryArgs;
 
@@ -10,6 +10,7 @@ use super::EntryArgs;
 #[darling(attributes(model))]
 pub(crate) struct ModelArgs {
     pub ident: Ident,
+    pub vis: Visibility,
     pub data: Data<Ignored, EntryArgs>,
 
     /// The table type, either `table` or `multimap`.
@@ -20,9 +21,28 @@ pub(crate) struct ModelArgs {
     pub impl_ext: Option<bool>,
     /// Implement `From<T>` for the given model. Requires implementing `ModelExt`.
     pub impl_from: Option<bool>,
+    /// Declare borrowed `redb` types for common owned field types.
+    pub infer_borrowed: Option<bool>,
+    /// The function validating the model.
+    pub validate: Option<Path>,
+    /// The model implements `ModelHooks`, rather than a no-op implementation.
+    pub hooks: Option<bool>,
+    /// Append a tombstone to the table value, marking soft removed models.
+    pub soft_delete: Option<bool>,
+    /// Implement `CsvModel` for the given model. Requires implementing `ModelExt`.
+    pub csv: Option<bool>,
+    /// Declare a patch struct, and implement `ModelPatch` for the given model.
+    pub patch: Option<bool>,
+    /// The traits derived by the patch struct, in addition to `Default`.
+    pub patch_derive: Option<PathList>,
+    /// Implement `ModelDiff` for the given model.
+    pub diff: Option<bool>,
+    /// Record the previous values of the given model in a history table.
+    /// Requires implementing `ModelExt`.
+    pub history: Option<bool>,
 }
 
-#[derive(FromMeta, Default)]
+#[derive(FromMeta, Default, PartialEq, Eq)]
 pub(crate) enum ModelTableType {
     #[default]
     Table,
@@ -17,10 +17,8 @@ impl Deref for RedbType {
     }
 }
 
-impl FromMeta for RedbType {
-    fn from_meta(item: &syn::Meta) -> darling::Result<Self> {
-        let mut ty = Type::from_meta(item)?;
-
+impl From<Type> for RedbType {
+    fn from(mut ty: Type) -> Self {
         // Declare redb lifetimes as 'static`.
         if let Type::Reference(ref mut ref_ty) = ty {
             ref_ty.lifetime = Some(Lifetime {
@@ -29,6 +27,12 @@ impl FromMeta for RedbType {
             })
         }
 
-        Ok(RedbType(ty))
+        RedbType(ty)
+    }
+}
+
+impl FromMeta for RedbType {
+    fn from_meta(item: &syn::Meta) -> darling::Result<Self> {
+        Type::from_meta(item).map(RedbType::from)
     }
 }
@@ -0,0 +1,71 @@
+use proc_macro::TokenStream;
+use quote::quote;
+
+use crate::{model, var};
+
+/// Implement `CsvModel`, converting each field with `ToString` and `FromStr`.
+pub(super) fn impl_csv_model(
+    m: &model::ModelMeta,
+    k: &var::ValueMeta,
+    v: &var::ValueMeta,
+) -> TokenStream {
+    let m_ident = m.ident();
+
+    let kv = var::ValueMeta::new_merged(k, v);
+    let kv_idents = kv.idents().collect::<Vec<_>>();
+    let kv_names = kv_idents.iter().map(|ident| ident.to_string());
+    let kv_keys = (0..kv.len()).map(|i| i < k.len());
+    let kv_indices = 0..kv.len();
+    let kv_errors = kv_idents
+        .iter()
+        .map(|ident| format!("invalid field `{}`: {{}}", ident))
+        .collect::<Vec<_>>();
+
+    let k_redb_ty = k.redb_ty();
+    let k_idents = k.idents().collect::<Vec<_>>();
+    let k_model_tys = k.iter().map(|var| var.model_ty());
+    let k_into_methods = k.into_methods();
+    let k_ident_tuple = k.composite_values();
+    let k_indices = 0..k.len();
+    let k_errors = &kv_errors[..k.len()];
+
+    quote! {
+        #[automatically_derived]
+        impl redb_model::CsvModel for #m_ident {
+            const FIELDS: &'static [redb_model::CsvField] = &[
+                #( redb_model::CsvField { name: #kv_names, key: #kv_keys } ), *
+            ];
+
+            fn to_record(&self) -> Vec<String> {
+                vec![ #( std::string::ToString::to_string(&self.#kv_idents) ), * ]
+            }
+
+            fn from_record(record: &[&str]) -> Result<Self, String> {
+                Ok(Self {
+                    #(
+                        #kv_idents: record[#kv_indices]
+                            .parse()
+                            .map_err(|e| format!(#kv_errors, e))?
+                    ), *
+                })
+            }
+
+            fn key_bytes(record: &[&str]) -> Result<Vec<u8>, String> {
+                // Parse the key.
+                let ( #( #k_idents ), * ): ( #( #k_model_tys ), * ) = ( #(
+                    record[#k_indices]
+                        .parse()
+                        .map_err(|e| format!(#k_errors, e))?
+                ), * );
+                // Apply type conversion.
+                let ( #( #k_idents ), * ) = ( #( &#k_idents ), * );
+                let ( #( #k_idents ), * ) = ( #( #k_into_methods ), *);
+                let key: <#k_redb_ty as redb::Value>::SelfType<'_> = #k_ident_tuple;
+
+                let bytes = <#k_redb_ty as redb::Value>::as_bytes(&key);
+                Ok(AsRef::<[u8]>::as_ref(&bytes).to_vec())
+            }
+        }
+    }
+    .into()
+}
@@ -16,11 +16,36 @@ pub(super) fn impl_model(
     let v_ty = v.redb_ty();
     let m_ty = m.redb_ty(&k_ty, &v_ty);
 
+    let m_table_ty = m.table_ty_name();
+    let k_ty_name = var::type_name(&k_ty);
+    let v_ty_name = var::type_name(&v_ty);
+    let kv = var::ValueMeta::new_merged(k, v);
+    let kv_names = kv.idents().map(|ident| ident.to_string());
+    let kv_keys = (0..kv.len()).map(|i| i < k.len());
+    let kv_ty_names = kv.iter().map(|var| var::type_name(var.model_ty()));
+    let kv_redb_ty_names = kv.iter().map(|var| var::type_name(var.redb_ty()));
+
     quote! {
         #[automatically_derived]
         impl<'a> Model<'a> for #m_ident {
                 type TableType = #m_ty;
                 const DEFINITION: Self::TableType = <#m_ty>::new(#m_name);
+                const SCHEMA: redb_model::ModelSchema = redb_model::ModelSchema {
+                    name: #m_name,
+                    table_type: #m_table_ty,
+                    key_type: #k_ty_name,
+                    value_type: #v_ty_name,
+                    fields: &[
+                        #(
+                            redb_model::FieldSchema {
+                                name: #kv_names,
+                                key: #kv_keys,
+                                ty: #kv_ty_names,
+                                redb_type: #kv_redb_ty_names,
+                            }
+                        ), *
+                    ],
+                };
         }
     }
     .into()
@@ -0,0 +1,38 @@
+use proc_macro::TokenStream;
+use quote::quote;
+
+use crate::{model, var};
+
+/// Implement `ModelDiff`, comparing each field with `PartialEq` and formatting
+/// changed values with `Debug`.
+pub(super) fn impl_model_diff(
+    m: &model::ModelMeta,
+    k: &var::ValueMeta,
+    v: &var::ValueMeta,
+) -> TokenStream {
+    let m_ident = m.ident();
+
+    let kv = var::ValueMeta::new_merged(k, v);
+    let kv_idents = kv.idents().collect::<Vec<_>>();
+    let kv_names = kv_idents.iter().map(|ident| ident.to_string());
+
+    quote! {
+        #[automatically_derived]
+        impl redb_model::ModelDiff for #m_ident {
+            fn diff(&self, other: &Self) -> Vec<redb_model::FieldChange> {
+                let mut changes = Vec::new();
+                #(
+                    if self.#kv_idents != other.#kv_idents {
+                        changes.push(redb_model::FieldChange {
+                            field: #kv_names,
+                            before: format!("{:?}", self.#kv_idents),
+                            after: format!("{:?}", other.#kv_idents),
+                        });
+                    }
+                )*
+                changes
+            }
+        }
+    }
+    .into()
+}
@@ -1,6 +1,8 @@
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::parse_quote;
 
+use crate::args::EntryArgs;
 use crate::{model, var};
 
 /// Implement `ModelExt`.
@@ -20,12 +22,19 @@ pub(super) fn impl_model_ext(
     let as_key = def_as_key(&k);
     let as_value = def_as_value(&v);
     let as_values = def_as_key_and_value(&k, &v);
+    let validate = def_validate(&m, &k, &v);
+    let timestamps = def_timestamps(&k, &v);
+    let tombstone = def_tombstone(&v);
+    let version = def_version(&v);
+    let history = def_history(&m);
 
     quote! {
         #[automatically_derived]
+        #[allow(clippy::unused_unit)]
         impl<'a> redb_model::ModelExt<'a> for #m_ident{
             #redb_alias
             #model_alias
+            #history
 
             #from_values
             #from_guards
@@ -33,11 +42,23 @@ pub(super) fn impl_model_ext(
             #as_key
             #as_value
             #as_values
+            #validate
+            #timestamps
+            #tombstone
+            #version
         }
     }
     .into()
 }
 
+/// Define the `ModelExt::HISTORY` table name, if the model records history.
+fn def_history(m: &model::ModelMeta) -> proc_macro2::TokenStream {
+    match m.history_name() {
+        Some(name) => quote! { const HISTORY: Option<&'static str> = Some(#name); },
+        None => quote! {},
+    }
+}
+
 /// Define the `ModelExt::RedbKey` and `ModelExt::RedbValue`.
 fn def_redb_alias(k: &var::ValueMeta, v: &var::ValueMeta) -> proc_macro2::TokenStream {
     let k_ty = k.redb_ty();
@@ -86,7 +107,10 @@ fn def_from_values(
             )
         ) -> Self {
             let (#k_ident_tuple, #v_ident_tuple) = (values.0, values.1);
-            #m_ident { #( #kv_idents: #kv_from_methods), * }
+            let mut model = #m_ident { #( #kv_idents: #kv_from_methods), * };
+            redb_model::ModelHooks::after_load(&mut model);
+
+            model
         }
     }
 }
@@ -116,9 +140,12 @@ fn def_from_guards(
             // Apply type conversion.
             let ( #( #kv_idents ), * ) = ( #( #kv_from_methods ), *);
 
-            #m_ident {
+            let mut model = #m_ident {
                 #( #kv_idents ), *
-            }
+            };
+            redb_model::ModelHooks::after_load(&mut model);
+
+            model
         }
     }
 }
@@ -152,9 +179,12 @@ fn def_from_key_and_guard(
             // Apply type conversion.
             let ( #( #kv_idents ), * ) = ( #( #kv_from_methods ), *);
 
-            #m_ident {
+            let mut model = #m_ident {
                 #( #kv_idents ), *
-            }
+            };
+            redb_model::ModelHooks::after_load(&mut model);
+
+            model
         }
     }
 }
@@ -164,7 +194,7 @@ fn def_as_key(k: &var::ValueMeta) -> proc_macro2::TokenStream {
     let k_redb_ty = k.redb_ty();
     let k_idents = k.idents().collect::<Vec<_>>();
     let k_into_methods = k.into_methods();
-    let k_ident_tuple = k.composite_idents();
+    let k_ident_tuple = k.composite_values();
 
     quote! {
         fn as_key (&'a self) -> <#k_redb_ty as redb::Value>::SelfType<'a> {
@@ -183,7 +213,7 @@ fn def_as_value(v: &var::ValueMeta) -> proc_macro2::TokenStream {
     let v_redb_ty = v.redb_ty();
     let v_idents = v.idents().collect::<Vec<_>>();
     let v_into_methods = v.into_methods();
-    let v_ident_tuple = v.composite_idents();
+    let v_ident_tuple = v.composite_values();
 
     quote! {
         fn as_value (&'a self) -> <#v_redb_ty as redb::Value>::SelfType<'a> {
@@ -201,8 +231,8 @@ fn def_as_key_and_value(k: &var::ValueMeta, v: &var::ValueMeta) -> proc_macro2::
     let k_redb_ty = k.redb_ty();
     let v_redb_ty = v.redb_ty();
 
-    let k_ident_tuple = k.composite_idents();
-    let v_ident_tuple = v.composite_idents();
+    let k_ident_tuple = k.composite_values();
+    let v_ident_tuple = v.composite_values();
 
     let kv = var::ValueMeta::new_merged(k, v);
     let kv_idents = kv.idents().collect::<Vec<_>>();
@@ -224,3 +254,130 @@ fn def_as_key_and_value(k: &var::ValueMeta, v: &var::ValueMeta) -> proc_macro2::
         }
     }
 }
+
+/// Define the `Model::validate` method, if any `validate` function is declared.
+fn def_validate(
+    m: &model::ModelMeta,
+    k: &var::ValueMeta,
+    v: &var::ValueMeta,
+) -> proc_macro2::TokenStream {
+    let kv = var::ValueMeta::new_merged(k, v);
+    let (kv_idents, kv_validate): (Vec<_>, Vec<_>) = kv
+        .iter()
+        .filter_map(|var| var.validate().map(|validate| (var.ident(), validate)))
+        .unzip();
+    let kv_names = kv_idents.iter().map(|ident| ident.to_string());
+    let m_validate = m.validate().into_iter();
+
+    if kv_validate.is_empty() && m.validate().is_none() {
+        return quote! {};
+    }
+
+    quote! {
+        fn validate(&self) -> Result<(), redb_model::ValidationError> {
+            // Validate fields.
+            #(
+                #kv_validate(&self.#kv_idents)
+                    .map_err(|e| redb_model::ValidationError::field(#kv_names, e))?;
+            )*
+            // Validate the model.
+            #(
+                #m_validate(self).map_err(redb_model::ValidationError::model)?;
+            )*
+
+            Ok(())
+        }
+    }
+}
+
+/// Define the `Model::set_created_at`, `Model::copy_created_at` and
+/// `Model::set_updated_at` methods, if any `created_at` or `updated_at` field is
+/// declared.
+fn def_timestamps(k: &var::ValueMeta, v: &var::ValueMeta) -> proc_macro2::TokenStream {
+    let kv = var::ValueMeta::new_merged(k, v);
+    let created_idents = kv
+        .iter()
+        .filter(|var| var.created_at())
+        .map(|var| var.ident())
+        .collect::<Vec<_>>();
+    let updated_idents = kv
+        .iter()
+        .filter(|var| var.updated_at())
+        .map(|var| var.ident())
+        .collect::<Vec<_>>();
+
+    let set_created_at = match created_idents.is_empty() {
+        true => quote! {},
+        false => quote! {
+            fn set_created_at(&mut self, now: std::time::SystemTime) {
+                #( self.#created_idents = redb_model::Timestamp::from_system_time(now); )*
+            }
+
+            fn copy_created_at(&mut self, model: &Self) {
+                #( self.#created_idents = std::clone::Clone::clone(&model.#created_idents); )*
+            }
+        },
+    };
+    let set_updated_at = match updated_idents.is_empty() {
+        true => quote! {},
+        false => quote! {
+            fn set_updated_at(&mut self, now: std::time::SystemTime) {
+                #( self.#updated_idents = redb_model::Timestamp::from_system_time(now); )*
+            }
+        },
+    };
+
+    quote! {
+        #set_created_at
+        #set_updated_at
+    }
+}
+
+/// Define the `ModelExt::version` and `ModelExt::set_version` methods, if a
+/// `version` field is declared.
+fn def_version(v: &var::ValueMeta) -> proc_macro2::TokenStream {
+    let Some(version_ident) = v.iter().find(|var| var.version()).map(|var| var.ident()) else {
+        return quote! {};
+    };
+
+    quote! {
+        fn version(&self) -> Option<u64> {
+            Some(self.#version_ident)
+        }
+
+        fn set_version(&mut self, version: u64) {
+            self.#version_ident = version;
+        }
+    }
+}
+
+/// Define the `ModelExt::SOFT_DELETE` flag, and the `ModelExt::is_deleted` and
+/// `ModelExt::with_deleted` methods, if the value declares a tombstone.
+fn def_tombstone(v: &var::ValueMeta) -> proc_macro2::TokenStream {
+    if !v.tombstone() {
+        return quote! {};
+    }
+
+    let v_redb_ty = v.redb_ty();
+    let v_tombstone = v.composite(|_| parse_quote!(_), parse_quote!(__tombstone));
+    let v_ident_pattern = v.composite(EntryArgs::ident_expr, parse_quote!(_));
+    let v_ident_tuple = v.composite_idents();
+
+    quote! {
+        const SOFT_DELETE: bool = true;
+
+        fn is_deleted(value: <#v_redb_ty as redb::Value>::SelfType<'_>) -> bool {
+            let #v_tombstone = value;
+            __tombstone
+        }
+
+        fn with_deleted<'v>(
+            value: <#v_redb_ty as redb::Value>::SelfType<'v>,
+            deleted: bool
+        ) -> <#v_redb_ty as redb::Value>::SelfType<'v> {
+            let #v_ident_pattern = value;
+            let __tombstone = deleted;
+            #v_ident_tuple
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use proc_macro::TokenStream;
+use quote::quote;
+
+use crate::model;
+
+/// Implement `ModelHooks` with the default no-op hooks.
+pub(super) fn impl_model_hooks(m: &model::ModelMeta) -> TokenStream {
+    let m_ident = m.ident();
+
+    quote! {
+        #[automatically_derived]
+        impl redb_model::ModelHooks for #m_ident {}
+    }
+    .into()
+}
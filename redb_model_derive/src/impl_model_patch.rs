@@ -0,0 +1,53 @@
+use proc_macro::TokenStream;
+use quote::quote;
+
+use crate::{model, var};
+
+/// Declare the patch struct, with an optional field of each value field, and
+/// implement `ModelPatch`. The `version`, `created_at` and `updated_at` fields are
+/// set by writes, and are not patched.
+pub(super) fn impl_model_patch(m: &model::ModelMeta, v: &var::ValueMeta) -> TokenStream {
+    let m_ident = m.ident();
+    let m_vis = m.vis();
+    let p_ident = m.patch_ident();
+    let p_derive = m.patch_derive();
+    let p_doc = format!(
+        "A partial update of `{}`, replacing each value field that is `Some`.",
+        m_ident
+    );
+
+    let v_vars = v
+        .iter()
+        .filter(|var| !(var.version() || var.created_at() || var.updated_at()))
+        .collect::<Vec<_>>();
+    let v_idents = v_vars.iter().map(|var| var.ident()).collect::<Vec<_>>();
+    let v_docs = v_idents
+        .iter()
+        .map(|ident| format!("The `{}` field, if replaced.", ident));
+    let v_model_tys = v_vars.iter().map(|var| var.model_ty());
+
+    quote! {
+        #[doc = #p_doc]
+        #[derive(Default, #( #p_derive ), *)]
+        #m_vis struct #p_ident {
+            #(
+                #[doc = #v_docs]
+                #m_vis #v_idents: Option<#v_model_tys>,
+            )*
+        }
+
+        #[automatically_derived]
+        impl redb_model::ModelPatch for #m_ident {
+            type Patch = #p_ident;
+
+            fn apply_patch(&mut self, patch: Self::Patch) {
+                #(
+                    if let Some(value) = patch.#v_idents {
+                        self.#v_idents = value;
+                    }
+                )*
+            }
+        }
+    }
+    .into()
+}
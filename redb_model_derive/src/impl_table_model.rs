@@ -0,0 +1,29 @@
+use proc_macro::TokenStream;
+use quote::quote;
+
+use crate::args::ModelTableType;
+use crate::{model, var};
+
+/// Implement `TableModel`, if the model is stored in a table.
+pub(super) fn impl_table_model(
+    m: &model::ModelMeta,
+    k: &var::ValueMeta,
+    v: &var::ValueMeta,
+) -> TokenStream {
+    if *m.table_ty() != ModelTableType::Table {
+        return TokenStream::new();
+    }
+
+    let m_ident = m.ident();
+    let k_ty = k.redb_ty();
+    let v_ty = v.redb_ty();
+
+    quote! {
+        #[automatically_derived]
+        impl redb_model::TableModel for #m_ident {
+            type Key = #k_ty;
+            type Value = #v_ty;
+        }
+    }
+    .into()
+}
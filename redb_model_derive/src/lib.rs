@@ -7,9 +7,14 @@ mod args;
 mod model;
 mod var;
 
+mod impl_csv_model;
 mod impl_from;
 mod impl_model;
+mod impl_model_diff;
 mod impl_model_ext;
+mod impl_model_hooks;
+mod impl_model_patch;
+mod impl_table_model;
 
 /// Unwraps a `Result<T, darling::Error>`, or returns the error as a token stream.
 macro_rules! unwrap_token_stream {
@@ -34,17 +39,48 @@ pub fn Model(item: TokenStream) -> TokenStream {
 
     let impl_from = struct_args.impl_from;
     let impl_ext = struct_args.impl_ext;
+    let infer_borrowed = struct_args.infer_borrowed;
+    let hooks = struct_args.hooks;
+    let soft_delete = struct_args.soft_delete;
+    let csv = struct_args.csv;
+    let patch = struct_args.patch;
+    let diff = struct_args.diff;
 
-    let (k_fields, v_fields): (Vec<_>, Vec<_>) = fields_args
+    let (mut k_fields, mut v_fields): (Vec<_>, Vec<_>) = fields_args
         .into_iter()
+        .map(|mut field| {
+            if Some(true) == infer_borrowed {
+                field.infer_borrowed();
+            }
+            field
+        })
         .partition(|field| *field.position() == args::EntryPosition::Key);
+    let mut errors = darling::Error::accumulator();
+    errors.handle(var::sort(&mut k_fields));
+    errors.handle(var::sort(&mut v_fields));
+    errors.handle(var::check_version(&k_fields, &v_fields));
+    unwrap_token_stream!(errors.finish());
+
+    // History
+    if Some(true) == struct_args.history {
+        if !(Some(true) == impl_ext) {
+            return TokenStream::from(darling::Error::missing_field("impl_ext").write_errors());
+        }
+        if struct_args.table_type == Some(args::ModelTableType::Multimap) {
+            return TokenStream::from(
+                darling::Error::custom("`history` is not supported by multimap tables")
+                    .with_span(&struct_args.ident)
+                    .write_errors(),
+            );
+        }
+    }
 
     // Model
     let m = model::ModelMeta::new(struct_args);
     // Key
     let k = var::ValueMeta::new(&k_fields);
     // Value
-    let v = var::ValueMeta::new(&v_fields);
+    let v = var::ValueMeta::new(&v_fields).with_tombstone(Some(true) == soft_delete);
 
     let mut stream = TokenStream::new();
 
@@ -53,6 +89,12 @@ pub fn Model(item: TokenStream) -> TokenStream {
     // impl ModelExt
     if Some(true) == impl_ext {
         stream.extend(impl_model_ext::impl_model_ext(&m, &k, &v));
+        // impl ModelHooks
+        if !(Some(true) == hooks) {
+            stream.extend(impl_model_hooks::impl_model_hooks(&m));
+        }
+        // impl TableModel
+        stream.extend(impl_table_model::impl_table_model(&m, &k, &v));
     }
     // impl From<T>
     if Some(true) == impl_from {
@@ -61,6 +103,21 @@ pub fn Model(item: TokenStream) -> TokenStream {
         }
         stream.extend(impl_from::impl_from(&m, &k, &v));
     }
+    // impl CsvModel
+    if Some(true) == csv {
+        if !(Some(true) == impl_ext) {
+            return TokenStream::from(darling::Error::missing_field("impl_ext").write_errors());
+        }
+        stream.extend(impl_csv_model::impl_csv_model(&m, &k, &v));
+    }
+    // impl ModelPatch
+    if Some(true) == patch {
+        stream.extend(impl_model_patch::impl_model_patch(&m, &v));
+    }
+    // impl ModelDiff
+    if Some(true) == diff {
+        stream.extend(impl_model_diff::impl_model_diff(&m, &k, &v));
+    }
 
     stream
 }
@@ -3,7 +3,7 @@ use syn::{
     punctuated::Punctuated,
     token::{Comma, PathSep},
     AngleBracketedGenericArguments, GenericArgument, Ident, Lifetime, Path, PathArguments,
-    PathSegment, Token, Type, TypePath,
+    PathSegment, Token, Type, TypePath, Visibility,
 };
 
 use crate::args::{ModelArgs, ModelTableType};
@@ -11,9 +11,13 @@ use crate::args::{ModelArgs, ModelTableType};
 /// Metadata for table definitions.
 pub(super) struct ModelMeta {
     ident: Ident,
+    vis: Visibility,
     name: String,
 
     table_ty: ModelTableType,
+    validate: Option<Path>,
+    patch_derive: Vec<Path>,
+    history: bool,
 }
 
 impl ModelMeta {
@@ -22,11 +26,22 @@ impl ModelMeta {
         let ident = args.ident;
         let name = args.name.unwrap_or_else(|| ident.to_string());
         let table_ty = args.table_type.unwrap_or(Default::default());
+        let validate = args.validate;
+        let patch_derive = args
+            .patch_derive
+            .map(|paths| paths.to_vec())
+            .unwrap_or_default();
+
+        let history = Some(true) == args.history;
 
         Self {
             ident,
+            vis: args.vis,
             name,
             table_ty,
+            validate,
+            patch_derive,
+            history,
         }
     }
 
@@ -35,11 +50,49 @@ impl ModelMeta {
         &self.ident
     }
 
+    /// Get the table visibility.
+    pub(super) fn vis(&self) -> &Visibility {
+        &self.vis
+    }
+
     /// Get the table `name`.
     pub(super) fn name(&self) -> &str {
         &self.name
     }
 
+    /// Get the table type.
+    pub(super) fn table_ty(&self) -> &ModelTableType {
+        &self.table_ty
+    }
+
+    /// Get the table type name.
+    pub(super) fn table_ty_name(&self) -> &'static str {
+        match self.table_ty {
+            ModelTableType::Table => "table",
+            ModelTableType::Multimap => "multimap",
+        }
+    }
+
+    /// Get the function validating the model.
+    pub(super) fn validate(&self) -> Option<&Path> {
+        self.validate.as_ref()
+    }
+
+    /// Get the patch struct `Ident`.
+    pub(super) fn patch_ident(&self) -> Ident {
+        Ident::new(&format!("{}Patch", self.ident), self.ident.span())
+    }
+
+    /// Get the traits derived by the patch struct.
+    pub(super) fn patch_derive(&self) -> &[Path] {
+        &self.patch_derive
+    }
+
+    /// Get the history table `name`, if the model records history.
+    pub(super) fn history_name(&self) -> Option<String> {
+        self.history.then(|| format!("{}_history", self.name))
+    }
+
     /// The table, or multimap table definition as a generic type.
     pub(crate) fn redb_ty(&self, k: &Type, v: &Type) -> Type {
         // Generic argumemnts.
@@ -1,18 +1,90 @@
 //! Table variable interpolation.
 use std::ops::Deref;
 
-use syn::{Expr, ExprTuple, Ident, Type, TypeTuple};
+use quote::ToTokens;
+use syn::{parse_quote, Expr, ExprTuple, Ident, Type, TypeTuple};
 
 use crate::args::EntryArgs;
 
+/// The largest tuple for which `redb` implements `Key` and `Value`.
+const MAX_TUPLE_ARITY: usize = 12;
+
+/// Sort fields by the declared `order`, retaining the declaration order if
+/// no field declares an `order`. Orders must be declared on every field, or
+/// none, and form a contiguous sequence starting from `0`.
+pub(crate) fn sort(fields: &mut [EntryArgs]) -> darling::Result<()> {
+    if fields.iter().all(|field| field.order().is_none()) {
+        return Ok(());
+    }
+
+    let mut errors = darling::Error::accumulator();
+    let mut declared = vec![None; fields.len()];
+    for field in fields.iter() {
+        match field.order() {
+            None => errors.push(
+                darling::Error::custom(
+                    "`order` must be declared on every field of the key or value if declared on any",
+                )
+                .with_span(field.ident()),
+            ),
+            Some(order) if order >= fields.len() => errors.push(
+                darling::Error::custom(format!(
+                    "`order` {} leaves a gap, expected an `order` less than {}",
+                    order,
+                    fields.len()
+                ))
+                .with_span(field.ident()),
+            ),
+            Some(order) => {
+                if let Some(other) = declared[order].replace(field.ident()) {
+                    errors.push(
+                        darling::Error::custom(format!(
+                            "`order` {} is already declared on `{}`",
+                            order, other
+                        ))
+                        .with_span(field.ident()),
+                    )
+                }
+            }
+        }
+    }
+    errors.finish()?;
+
+    fields.sort_by_key(|field| field.order());
+    Ok(())
+}
+
+/// Check that `version` is declared on, at most, a single value field.
+pub(crate) fn check_version(k_fields: &[EntryArgs], v_fields: &[EntryArgs]) -> darling::Result<()> {
+    let mut errors = darling::Error::accumulator();
+    for field in k_fields.iter().filter(|field| field.version()) {
+        errors.push(
+            darling::Error::custom("`version` must be declared on a value field")
+                .with_span(field.ident()),
+        );
+    }
+    for field in v_fields.iter().filter(|field| field.version()).skip(1) {
+        errors.push(
+            darling::Error::custom("`version` is already declared on another field")
+                .with_span(field.ident()),
+        );
+    }
+
+    errors.finish()
+}
+
 /// Metadata for table key/value composite type(s).
-pub(super) struct ValueMeta<'a>(Vec<&'a EntryArgs>);
+pub(super) struct ValueMeta<'a> {
+    vars: Vec<&'a EntryArgs>,
+    /// Append a `bool` tombstone to the composite type(s).
+    tombstone: bool,
+}
 
 impl<'a> Deref for ValueMeta<'a> {
     type Target = Vec<&'a EntryArgs>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.vars
     }
 }
 
@@ -22,7 +94,10 @@ impl<'a> ValueMeta<'a> {
     where
         I: IntoIterator<Item = &'a EntryArgs>,
     {
-        Self(fields.into_iter().collect())
+        Self {
+            vars: fields.into_iter().collect(),
+            tombstone: false,
+        }
     }
 
     /// Create a new `ValueMeta`, borrowing from two `ValueMeta` instances.
@@ -30,9 +105,20 @@ impl<'a> ValueMeta<'a> {
         Self::new(a.iter().chain(b.iter()).map(|var| *var))
     }
 
+    /// Append a `bool` tombstone to the composite type(s).
+    pub(crate) fn with_tombstone(mut self, tombstone: bool) -> Self {
+        self.tombstone = tombstone;
+        self
+    }
+
+    /// Whether the composite type(s) include a tombstone.
+    pub(crate) fn tombstone(&self) -> bool {
+        self.tombstone
+    }
+
     /// The type, or a tuple of the types within the model.
     pub(crate) fn model_ty(&self) -> Type {
-        match self.0.len() {
+        match self.len() {
             1 => self[0].model_ty().clone(),
             _ => {
                 let mut ty_el = syn::punctuated::Punctuated::new();
@@ -46,18 +132,18 @@ impl<'a> ValueMeta<'a> {
     }
 
     /// The type, or a tuple of the types within the database table definition.
+    /// Tuples exceeding `MAX_TUPLE_ARITY` are nested.
     pub(crate) fn redb_ty(&self) -> Type {
-        match self.len() {
-            1 => self[0].redb_ty().clone(),
-            _ => {
-                let mut ty_el = syn::punctuated::Punctuated::new();
-                ty_el.extend(self.iter().map(|var| var.redb_ty().to_owned()));
-                Type::Tuple(TypeTuple {
-                    paren_token: Default::default(),
-                    elems: ty_el,
-                })
-            }
+        let mut ty: Vec<Type> = self.iter().map(|var| var.redb_ty().to_owned()).collect();
+        if self.tombstone {
+            ty.push(parse_quote!(bool));
         }
+        nest(ty, &|ty_el| {
+            Type::Tuple(TypeTuple {
+                paren_token: Default::default(),
+                elems: ty_el.into_iter().collect(),
+            })
+        })
     }
 
     /// Get an `Expr` of all idents.
@@ -65,20 +151,33 @@ impl<'a> ValueMeta<'a> {
         self.iter().map(|var| var.ident())
     }
 
-    /// Get an `Expr` of idents as either a single ident, or a tuple of idents.
+    /// Get an `Expr` of idents as either a single ident, or a tuple of idents,
+    /// binding any tombstone as `__tombstone`.
     pub(crate) fn composite_idents(&self) -> Expr {
-        match self.len() {
-            1 => self[0].ident_expr(),
-            _ => {
-                let mut elems = syn::punctuated::Punctuated::new();
-                elems.extend(self.iter().map(|var| var.ident_expr()));
-                Expr::Tuple(ExprTuple {
-                    attrs: vec![],
-                    paren_token: Default::default(),
-                    elems,
-                })
-            }
+        self.composite(EntryArgs::ident_expr, parse_quote!(__tombstone))
+    }
+
+    /// Get an `Expr` of idents as either a single ident, or a tuple of idents,
+    /// declaring any tombstone as `false`.
+    pub(crate) fn composite_values(&self) -> Expr {
+        self.composite(EntryArgs::ident_expr, parse_quote!(false))
+    }
+
+    /// Get an `Expr` of the given field and tombstone `Expr`s as either a single
+    /// `Expr`, or a tuple. Tuples exceeding `MAX_TUPLE_ARITY` are nested,
+    /// matching `redb_ty`.
+    pub(crate) fn composite(&self, field: impl Fn(&EntryArgs) -> Expr, tombstone: Expr) -> Expr {
+        let mut elems: Vec<Expr> = self.iter().map(|var| field(var)).collect();
+        if self.tombstone {
+            elems.push(tombstone);
         }
+        nest(elems, &|elems| {
+            Expr::Tuple(ExprTuple {
+                attrs: vec![],
+                paren_token: Default::default(),
+                elems: elems.into_iter().collect(),
+            })
+        })
     }
 
     /// Get an `Expr` of ident `from` calls.
@@ -91,3 +190,40 @@ impl<'a> ValueMeta<'a> {
         self.iter().map(|var| var.into_op())
     }
 }
+
+/// Get a type as a string, as it would be written.
+pub(crate) fn type_name(ty: &Type) -> String {
+    let tokens = ty.to_token_stream().to_string();
+    let chars = tokens.chars().collect::<Vec<_>>();
+
+    // Retain whitespace between words, and following commas.
+    let is_word = |c: Option<&char>| c.is_some_and(|c| c.is_alphanumeric() || *c == '_');
+    chars
+        .iter()
+        .enumerate()
+        .filter(|(i, c)| {
+            let prev = i.checked_sub(1).and_then(|i| chars.get(i));
+            **c != ' ' || (is_word(prev) && is_word(chars.get(i + 1))) || prev == Some(&',')
+        })
+        .map(|(_, c)| c)
+        .collect()
+}
+
+/// Combine elements into a single element, or a tuple of elements. Tuples
+/// exceeding `MAX_TUPLE_ARITY` are split into tuples of `MAX_TUPLE_ARITY`
+/// elements, and nested until the outermost tuple is within the limit.
+fn nest<T>(mut elems: Vec<T>, tuple: &impl Fn(Vec<T>) -> T) -> T {
+    match elems.len() {
+        1 => elems.remove(0),
+        len if len <= MAX_TUPLE_ARITY => tuple(elems),
+        _ => {
+            let mut chunks = Vec::new();
+            let mut elems = elems.into_iter().peekable();
+            while elems.peek().is_some() {
+                let chunk = elems.by_ref().take(MAX_TUPLE_ARITY).collect();
+                chunks.push(nest(chunk, tuple));
+            }
+            nest(chunks, tuple)
+        }
+    }
+}
@@ -1,4 +1,6 @@
 //! Traits for the `redb_model` crate.
+use std::fmt;
+use std::time::SystemTime;
 
 /// Trait for table definition.
 pub trait Model<'a> {
@@ -6,10 +8,151 @@ pub trait Model<'a> {
     type TableType;
     /// The table definition.
     const DEFINITION: Self::TableType;
+    /// The schema of the model.
+    const SCHEMA: ModelSchema;
+    /// The fingerprint of the model schema. See [`ModelSchema::hash`].
+    const SCHEMA_HASH: u64 = Self::SCHEMA.hash();
 }
 
+/// The schema of a model, describing the table and fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelSchema {
+    /// The table name.
+    pub name: &'static str,
+    /// The table type, either `table` or `multimap`.
+    pub table_type: &'static str,
+    /// The `redb` key type.
+    pub key_type: &'static str,
+    /// The `redb` value type.
+    pub value_type: &'static str,
+    /// The fields of the key, followed by the fields of the value, in tuple order.
+    pub fields: &'static [FieldSchema],
+}
+
+impl ModelSchema {
+    /// Compute the fingerprint of the schema from the table name, table type, `redb`
+    /// key and value types, and the name, position and `redb` type of each field,
+    /// in order. The spelling of each `redb` type is normalized, ignoring whitespace,
+    /// lifetimes and paths, such that `std::string::String` and `String` are equal,
+    /// while type aliases are not resolved.
+    ///
+    /// The hash differs when fields are renamed, reordered, added or removed, or
+    /// their `redb` types change, including changes that keep the stored layout,
+    /// such as renaming a field. Once a table is migrated, or found compatible, the
+    /// schema is registered again with `schema::register`.
+    pub const fn hash(&self) -> u64 {
+        let mut hash = hash_str(FNV_OFFSET, self.name);
+        hash = hash_str(hash, self.table_type);
+        hash = hash_type(hash, self.key_type);
+        hash = hash_type(hash, self.value_type);
+
+        let mut i = 0;
+        while i < self.fields.len() {
+            let field = &self.fields[i];
+            hash = hash_str(hash, field.name);
+            hash = hash_byte(hash, field.key as u8);
+            hash = hash_type(hash, field.redb_type);
+            i += 1;
+        }
+        hash
+    }
+}
+
+/// The FNV-1a offset basis.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+/// The FNV-1a prime.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Hash the byte with FNV-1a.
+const fn hash_byte(hash: u64, byte: u8) -> u64 {
+    (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+}
+
+/// Hash the string with FNV-1a, followed by a byte never found in UTF-8, such
+/// that consecutive strings are not ambiguous.
+const fn hash_str(mut hash: u64, s: &str) -> u64 {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        hash = hash_byte(hash, bytes[i]);
+        i += 1;
+    }
+    hash_byte(hash, 0xff)
+}
+
+/// Hash the type with FNV-1a as `hash_str`, skipping whitespace and lifetimes, and
+/// hashing only the last segment of each path.
+const fn hash_type(mut hash: u64, ty: &str) -> u64 {
+    let bytes = ty.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        if byte == b'\'' {
+            i += 1;
+            while i < bytes.len() && is_ident(bytes[i]) {
+                i += 1;
+            }
+        } else if is_ident(byte) || byte == b':' {
+            let mut segment = i;
+            while i < bytes.len() && (is_ident(bytes[i]) || bytes[i] == b':') {
+                if bytes[i] == b':' {
+                    segment = i + 1;
+                }
+                i += 1;
+            }
+            while segment < i {
+                hash = hash_byte(hash, bytes[segment]);
+                segment += 1;
+            }
+        } else {
+            if !byte.is_ascii_whitespace() {
+                hash = hash_byte(hash, byte);
+            }
+            i += 1;
+        }
+    }
+    hash_byte(hash, 0xff)
+}
+
+/// Whether the byte is of an identifier.
+const fn is_ident(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+/// The schema of a model field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldSchema {
+    /// The field name.
+    pub name: &'static str,
+    /// Whether the field is of the key, rather than the value.
+    pub key: bool,
+    /// The field type declared within the model.
+    pub ty: &'static str,
+    /// The field type declared within the database.
+    pub redb_type: &'static str,
+}
+
+/// Hooks called when a model is inserted, loaded or removed. All hooks are
+/// no-op by default.
+pub trait ModelHooks {
+    /// Called before the model is inserted into a table.
+    fn before_insert(&mut self) {}
+
+    /// Called after the model is instantiated from `redb` values.
+    fn after_load(&mut self) {}
+
+    /// Called before the model is removed from a table.
+    fn before_remove(&self) {}
+}
+
+/// A `redb` (`K`, `V`) pair of the model `M`.
+type KeyAndValue<'a, M> = (
+    <<M as ModelExt<'a>>::RedbKey as redb::Value>::SelfType<'a>,
+    <<M as ModelExt<'a>>::RedbValue as redb::Value>::SelfType<'a>,
+);
+
 /// Conversion methods for a `Model` and the associated keys and values.
-pub trait ModelExt<'a>: Model<'a> + Sized + 'a {
+pub trait ModelExt<'a>: Model<'a> + ModelHooks + Sized + 'a {
     /// The `redb` definition key type(s).
     type RedbKey: redb::Key;
     /// The `redb` definition value type(s).
@@ -20,6 +163,14 @@ pub trait ModelExt<'a>: Model<'a> + Sized + 'a {
     /// The model key type(s).
     type ModelValue;
 
+    /// The name of the history table, if declared with the `history` attribute.
+    /// Models declaring a history table are written with a `WriteTxn`, recording
+    /// the history of each write.
+    const HISTORY: Option<&'static str> = None;
+
+    /// Whether the model declares `soft_delete`, such that it may be soft removed.
+    const SOFT_DELETE: bool = false;
+
     /// Instantiate from a `redb` (`K`, `V`) pair.
     fn from_values(
         values: (
@@ -60,4 +211,164 @@ pub trait ModelExt<'a>: Model<'a> + Sized + 'a {
         <Self::RedbKey as redb::Value>::SelfType<'a>,
         <Self::RedbValue as redb::Value>::SelfType<'a>,
     );
+
+    /// Set the `created_at` field(s) to the given time.
+    fn set_created_at(&mut self, _now: SystemTime) {}
+
+    /// Set the `created_at` field(s) to those of the given model, such as the
+    /// stored model replaced by an update.
+    fn copy_created_at(&mut self, _model: &Self) {}
+
+    /// Set the `updated_at` field(s) to the given time.
+    fn set_updated_at(&mut self, _now: SystemTime) {}
+
+    /// Get the `version` field, if declared.
+    fn version(&self) -> Option<u64> {
+        None
+    }
+
+    /// Set the `version` field, if declared.
+    fn set_version(&mut self, _version: u64) {}
+
+    /// Whether a `redb` value is marked as removed. Always `false` for models
+    /// without `soft_delete`.
+    fn is_deleted(_value: <Self::RedbValue as redb::Value>::SelfType<'_>) -> bool {
+        false
+    }
+
+    /// Set the removal mark of a `redb` value. Returns the value unchanged for
+    /// models without `soft_delete`.
+    fn with_deleted<'v>(
+        value: <Self::RedbValue as redb::Value>::SelfType<'v>,
+        _deleted: bool,
+    ) -> <Self::RedbValue as redb::Value>::SelfType<'v> {
+        value
+    }
+
+    /// Validate the model with the declared `validate` functions.
+    fn validate(&self) -> Result<(), ValidationError> {
+        Ok(())
+    }
+
+    /// Validate the model, and get all variables as a `redb` `(K, V)` pair.
+    fn try_as_key_and_value(&'a self) -> Result<KeyAndValue<'a, Self>, ValidationError> {
+        self.validate()?;
+        Ok(self.as_key_and_value())
+    }
+
+    /// Instantiate and validate from a `redb` (`AccessGuard<K>`, `AccessGuard<V>`) pair.
+    fn try_from_guards(
+        values: (
+            &redb::AccessGuard<'a, Self::RedbKey>,
+            &redb::AccessGuard<'a, Self::RedbValue>,
+        ),
+    ) -> Result<Self, ValidationError> {
+        let model = Self::from_guards(values);
+        model.validate()?;
+        Ok(model)
+    }
+
+    /// Instantiate and validate from a `redb` (`K`, `AccessGuard<V>`) pair.
+    fn try_from_key_and_guard(
+        values: (
+            <Self::RedbKey as redb::Value>::SelfType<'a>,
+            &redb::AccessGuard<'a, Self::RedbValue>,
+        ),
+    ) -> Result<Self, ValidationError> {
+        let model = Self::from_key_and_guard(values);
+        model.validate()?;
+        Ok(model)
+    }
+}
+
+/// A partial update of a model, declared with the `patch` attribute.
+pub trait ModelPatch {
+    /// The patch struct, with an optional field of each value field.
+    type Patch;
+
+    /// Replace each value field that is `Some` within the patch.
+    fn apply_patch(&mut self, patch: Self::Patch);
+}
+
+/// A comparison of the fields of two models, declared with the `diff` attribute.
+pub trait ModelDiff {
+    /// Get the fields differing from the other model, in field order.
+    fn diff(&self, other: &Self) -> Vec<FieldChange>;
 }
+
+/// A field differing between two models.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    /// The field name.
+    pub field: &'static str,
+    /// The `Debug` formatted value of the field before the change.
+    pub before: String,
+    /// The `Debug` formatted value of the field after the change.
+    pub after: String,
+}
+
+/// A `ModelExt` model stored in a table, rather than a multimap table. Declares
+/// the `redb` key and value types independently of the `ModelExt` lifetime.
+pub trait TableModel:
+    for<'a> ModelExt<
+        'a,
+        RedbKey = <Self as TableModel>::Key,
+        RedbValue = <Self as TableModel>::Value,
+        TableType = redb::TableDefinition<
+            'a,
+            <Self as TableModel>::Key,
+            <Self as TableModel>::Value,
+        >,
+    > + 'static
+{
+    /// The `redb` definition key type(s).
+    type Key: redb::Key + 'static;
+    /// The `redb` definition value type(s).
+    type Value: redb::Value + 'static;
+}
+
+/// A model rejected by a `validate` function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    field: Option<&'static str>,
+    message: String,
+}
+
+impl ValidationError {
+    /// Create a new `ValidationError` for the given field.
+    pub fn field<E: fmt::Display>(field: &'static str, error: E) -> Self {
+        Self {
+            field: Some(field),
+            message: error.to_string(),
+        }
+    }
+
+    /// Create a new `ValidationError` for the model.
+    pub fn model<E: fmt::Display>(error: E) -> Self {
+        Self {
+            field: None,
+            message: error.to_string(),
+        }
+    }
+
+    /// The name of the rejected field, or `None` if rejected by the model.
+    pub fn field_name(&self) -> Option<&'static str> {
+        self.field
+    }
+
+    /// The message of the validation error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.field {
+            Some(field) => write!(f, "invalid field `{}`: {}", field, self.message),
+            None => write!(f, "invalid model: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
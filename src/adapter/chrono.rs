@@ -0,0 +1,60 @@
+//! `chrono` adapter.
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+
+use super::Adapter;
+
+/// Adapter for `chrono` types.
+///
+/// `DateTime<Utc>` and `NaiveDateTime` are stored as `(i64, u32)` seconds and
+/// subsecond nanoseconds relative to the unix epoch. `NaiveDate` is stored as
+/// `i32` days from the common era, and `NaiveTime` as `(u32, u32)` seconds
+/// from midnight and subsecond nanoseconds.
+pub struct Chrono;
+
+impl Adapter<DateTime<Utc>> for Chrono {
+    type RedbType = (i64, u32);
+
+    fn from_redb((secs, nanos): (i64, u32)) -> DateTime<Utc> {
+        DateTime::from_timestamp(secs, nanos).expect("Timestamp in range")
+    }
+
+    fn into_redb(value: &DateTime<Utc>) -> (i64, u32) {
+        (value.timestamp(), value.timestamp_subsec_nanos())
+    }
+}
+
+impl Adapter<NaiveDateTime> for Chrono {
+    type RedbType = (i64, u32);
+
+    fn from_redb(value: (i64, u32)) -> NaiveDateTime {
+        <Chrono as Adapter<DateTime<Utc>>>::from_redb(value).naive_utc()
+    }
+
+    fn into_redb(value: &NaiveDateTime) -> (i64, u32) {
+        <Chrono as Adapter<DateTime<Utc>>>::into_redb(&value.and_utc())
+    }
+}
+
+impl Adapter<NaiveDate> for Chrono {
+    type RedbType = i32;
+
+    fn from_redb(value: i32) -> NaiveDate {
+        NaiveDate::from_num_days_from_ce_opt(value).expect("Date in range")
+    }
+
+    fn into_redb(value: &NaiveDate) -> i32 {
+        value.num_days_from_ce()
+    }
+}
+
+impl Adapter<NaiveTime> for Chrono {
+    type RedbType = (u32, u32);
+
+    fn from_redb((secs, nanos): (u32, u32)) -> NaiveTime {
+        NaiveTime::from_num_seconds_from_midnight_opt(secs, nanos).expect("Time in range")
+    }
+
+    fn into_redb(value: &NaiveTime) -> (u32, u32) {
+        (value.num_seconds_from_midnight(), value.nanosecond())
+    }
+}
@@ -0,0 +1,239 @@
+//! Adapters declaring the `redb` type and conversion operations of common
+//! external types.
+//!
+//! An adapter is declared on a field with the `adapter` argument, either by the
+//! name of a built-in adapter, or by the path of a type implementing [`Adapter`]
+//! for the field type.
+//!
+//! Name | Adapter | Types | Feature
+//! ---|---|---|---
+//! `chrono` | [`Chrono`] | `DateTime<Utc>`, `NaiveDateTime`, `NaiveDate`, `NaiveTime` | `chrono`
+//! `net` | [`Net`] | `IpAddr`, `Ipv4Addr`, `Ipv6Addr`, `SocketAddr`, `SocketAddrV4`, `SocketAddrV6` | -
+//! `rust_decimal` | [`Decimal`] | `Decimal` | `rust_decimal`
+//! `std_path` | [`StdPath`] | `PathBuf` | -
+//! `std_time` | [`StdTime`] | `Duration`, `SystemTime` | -
+//! `time` | [`Time`] | `OffsetDateTime`, `PrimitiveDateTime`, `Date` | `time`
+//! `ulid` | [`Ulid`] | `Ulid` | `ulid`
+//! `uuid` | [`Uuid`] | `Uuid` | `uuid`
+//!
+//! ```rust
+//! # use redb_model::{Model, ModelExt};
+//! use std::net::IpAddr;
+//! use std::time::SystemTime;
+//!
+//! #[derive(Model)]
+//! #[model(impl_ext)]
+//! struct Session {
+//!     #[entry(position = "key", adapter = "net")]
+//!     address: IpAddr,
+//!     #[entry(position = "value", adapter = "std_time")]
+//!     started: SystemTime,
+//! }
+//! ```
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+#[cfg(unix)]
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "chrono")]
+mod chrono;
+#[cfg(feature = "rust_decimal")]
+mod rust_decimal;
+#[cfg(feature = "time")]
+mod time;
+#[cfg(feature = "ulid")]
+mod ulid;
+#[cfg(feature = "uuid")]
+mod uuid;
+
+#[cfg(feature = "chrono")]
+pub use self::chrono::Chrono;
+#[cfg(feature = "rust_decimal")]
+pub use self::rust_decimal::Decimal;
+#[cfg(feature = "time")]
+pub use self::time::Time;
+#[cfg(feature = "ulid")]
+pub use self::ulid::Ulid;
+#[cfg(feature = "uuid")]
+pub use self::uuid::Uuid;
+
+/// Declares the `redb` type of `T`, and the conversion operations between them.
+pub trait Adapter<T> {
+    /// The type declared in the `redb` table definition.
+    type RedbType: redb::Value + 'static;
+
+    /// Convert **from** the `redb` type.
+    fn from_redb(value: <Self::RedbType as redb::Value>::SelfType<'_>) -> T;
+
+    /// Convert a reference **into** the `redb` type.
+    fn into_redb(value: &T) -> <Self::RedbType as redb::Value>::SelfType<'_>;
+}
+
+/// Adapter for `std::net` addresses.
+///
+/// `IpAddr` is stored as `(u8, u128)`, declaring the IP version (`4` or `6`) and
+/// the address. Socket addresses append the port. `SocketAddrV6` flow info and
+/// scope IDs are not stored.
+///
+/// # Panics
+///
+/// Converting a stored IP version other than `4` or `6`.
+pub struct Net;
+
+impl Adapter<Ipv4Addr> for Net {
+    type RedbType = u32;
+
+    fn from_redb(value: u32) -> Ipv4Addr {
+        Ipv4Addr::from(value)
+    }
+
+    fn into_redb(value: &Ipv4Addr) -> u32 {
+        u32::from(*value)
+    }
+}
+
+impl Adapter<Ipv6Addr> for Net {
+    type RedbType = u128;
+
+    fn from_redb(value: u128) -> Ipv6Addr {
+        Ipv6Addr::from(value)
+    }
+
+    fn into_redb(value: &Ipv6Addr) -> u128 {
+        u128::from(*value)
+    }
+}
+
+impl Adapter<IpAddr> for Net {
+    type RedbType = (u8, u128);
+
+    fn from_redb((version, addr): (u8, u128)) -> IpAddr {
+        match version {
+            4 => IpAddr::V4(Ipv4Addr::from(addr as u32)),
+            6 => IpAddr::V6(Ipv6Addr::from(addr)),
+            _ => unreachable!("IP version {} is neither 4 nor 6", version),
+        }
+    }
+
+    fn into_redb(value: &IpAddr) -> (u8, u128) {
+        match value {
+            IpAddr::V4(addr) => (4, u32::from(*addr) as u128),
+            IpAddr::V6(addr) => (6, u128::from(*addr)),
+        }
+    }
+}
+
+impl Adapter<SocketAddrV4> for Net {
+    type RedbType = (u32, u16);
+
+    fn from_redb((addr, port): (u32, u16)) -> SocketAddrV4 {
+        SocketAddrV4::new(Ipv4Addr::from(addr), port)
+    }
+
+    fn into_redb(value: &SocketAddrV4) -> (u32, u16) {
+        (u32::from(*value.ip()), value.port())
+    }
+}
+
+impl Adapter<SocketAddrV6> for Net {
+    type RedbType = (u128, u16);
+
+    fn from_redb((addr, port): (u128, u16)) -> SocketAddrV6 {
+        SocketAddrV6::new(Ipv6Addr::from(addr), port, 0, 0)
+    }
+
+    fn into_redb(value: &SocketAddrV6) -> (u128, u16) {
+        (u128::from(*value.ip()), value.port())
+    }
+}
+
+impl Adapter<SocketAddr> for Net {
+    type RedbType = (u8, u128, u16);
+
+    fn from_redb((version, addr, port): (u8, u128, u16)) -> SocketAddr {
+        SocketAddr::new(<Net as Adapter<IpAddr>>::from_redb((version, addr)), port)
+    }
+
+    fn into_redb(value: &SocketAddr) -> (u8, u128, u16) {
+        let (version, addr) = <Net as Adapter<IpAddr>>::into_redb(&value.ip());
+        (version, addr, value.port())
+    }
+}
+
+/// Adapter for `std::path` types.
+///
+/// `PathBuf` is stored as `&[u8]`, the bytes of the path as encoded by the
+/// platform, such that paths that are not valid UTF-8 are retained. On platforms
+/// other than unix, paths are stored as UTF-8, and paths that are not valid
+/// unicode cannot be stored.
+///
+/// # Panics
+///
+/// On platforms other than unix, converting a path that is not valid unicode.
+pub struct StdPath;
+
+impl Adapter<PathBuf> for StdPath {
+    type RedbType = &'static [u8];
+
+    fn from_redb(value: &[u8]) -> PathBuf {
+        #[cfg(unix)]
+        let path = PathBuf::from(std::ffi::OsStr::from_bytes(value));
+        #[cfg(not(unix))]
+        let path = PathBuf::from(std::str::from_utf8(value).expect("Path is valid unicode"));
+
+        path
+    }
+
+    fn into_redb(value: &PathBuf) -> &[u8] {
+        #[cfg(unix)]
+        let bytes = value.as_os_str().as_bytes();
+        #[cfg(not(unix))]
+        let bytes = value.to_str().expect("Path is valid unicode").as_bytes();
+
+        bytes
+    }
+}
+
+/// Adapter for `std::time` types.
+///
+/// `Duration` is stored as `(u64, u32)` seconds and subsecond nanoseconds.
+/// `SystemTime` is stored as `(i64, u32)` seconds and subsecond nanoseconds
+/// relative to the unix epoch, preserving the order of times before the epoch.
+pub struct StdTime;
+
+impl Adapter<Duration> for StdTime {
+    type RedbType = (u64, u32);
+
+    fn from_redb((secs, nanos): (u64, u32)) -> Duration {
+        Duration::new(secs, nanos)
+    }
+
+    fn into_redb(value: &Duration) -> (u64, u32) {
+        (value.as_secs(), value.subsec_nanos())
+    }
+}
+
+impl Adapter<SystemTime> for StdTime {
+    type RedbType = (i64, u32);
+
+    fn from_redb((secs, nanos): (i64, u32)) -> SystemTime {
+        match secs {
+            0.. => UNIX_EPOCH + Duration::new(secs as u64, nanos),
+            _ => UNIX_EPOCH - Duration::new(secs.unsigned_abs(), 0) + Duration::new(0, nanos),
+        }
+    }
+
+    fn into_redb(value: &SystemTime) -> (i64, u32) {
+        match value.duration_since(UNIX_EPOCH) {
+            Ok(since) => (since.as_secs() as i64, since.subsec_nanos()),
+            Err(e) => {
+                let before = e.duration();
+                match before.subsec_nanos() {
+                    0 => (-(before.as_secs() as i64), 0),
+                    nanos => (-(before.as_secs() as i64) - 1, 1_000_000_000 - nanos),
+                }
+            }
+        }
+    }
+}
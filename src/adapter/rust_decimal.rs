@@ -0,0 +1,18 @@
+//! `rust_decimal` adapter.
+use super::Adapter;
+
+/// Adapter for `rust_decimal::Decimal`, stored as the `[u8; 16]` serialized
+/// form. Note that the serialized form does not preserve numeric order.
+pub struct Decimal;
+
+impl Adapter<rust_decimal::Decimal> for Decimal {
+    type RedbType = [u8; 16];
+
+    fn from_redb(value: [u8; 16]) -> rust_decimal::Decimal {
+        rust_decimal::Decimal::deserialize(value)
+    }
+
+    fn into_redb(value: &rust_decimal::Decimal) -> [u8; 16] {
+        value.serialize()
+    }
+}
@@ -0,0 +1,48 @@
+//! `time` adapter.
+use time::{Date, OffsetDateTime, PrimitiveDateTime};
+
+use super::Adapter;
+
+/// Adapter for `time` types.
+///
+/// `OffsetDateTime` is stored as `i128` nanoseconds relative to the unix epoch,
+/// and is loaded with a UTC offset. `PrimitiveDateTime` is assumed to be UTC
+/// and stored as an `OffsetDateTime`. `Date` is stored as the `i32` julian day.
+pub struct Time;
+
+impl Adapter<OffsetDateTime> for Time {
+    type RedbType = i128;
+
+    fn from_redb(value: i128) -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp_nanos(value).expect("Timestamp in range")
+    }
+
+    fn into_redb(value: &OffsetDateTime) -> i128 {
+        value.unix_timestamp_nanos()
+    }
+}
+
+impl Adapter<PrimitiveDateTime> for Time {
+    type RedbType = i128;
+
+    fn from_redb(value: i128) -> PrimitiveDateTime {
+        let value = <Time as Adapter<OffsetDateTime>>::from_redb(value);
+        PrimitiveDateTime::new(value.date(), value.time())
+    }
+
+    fn into_redb(value: &PrimitiveDateTime) -> i128 {
+        <Time as Adapter<OffsetDateTime>>::into_redb(&value.assume_utc())
+    }
+}
+
+impl Adapter<Date> for Time {
+    type RedbType = i32;
+
+    fn from_redb(value: i32) -> Date {
+        Date::from_julian_day(value).expect("Date in range")
+    }
+
+    fn into_redb(value: &Date) -> i32 {
+        value.to_julian_day()
+    }
+}
@@ -0,0 +1,17 @@
+//! `ulid` adapter.
+use super::Adapter;
+
+/// Adapter for `ulid::Ulid`, stored as `u128`.
+pub struct Ulid;
+
+impl Adapter<ulid::Ulid> for Ulid {
+    type RedbType = u128;
+
+    fn from_redb(value: u128) -> ulid::Ulid {
+        ulid::Ulid::from(value)
+    }
+
+    fn into_redb(value: &ulid::Ulid) -> u128 {
+        u128::from(*value)
+    }
+}
@@ -0,0 +1,17 @@
+//! `uuid` adapter.
+use super::Adapter;
+
+/// Adapter for `uuid::Uuid`, stored as `[u8; 16]`.
+pub struct Uuid;
+
+impl Adapter<uuid::Uuid> for Uuid {
+    type RedbType = [u8; 16];
+
+    fn from_redb(value: [u8; 16]) -> uuid::Uuid {
+        uuid::Uuid::from_bytes(value)
+    }
+
+    fn into_redb(value: &uuid::Uuid) -> [u8; 16] {
+        value.into_bytes()
+    }
+}
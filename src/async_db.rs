@@ -0,0 +1,257 @@
+//! Model operations on blocking threads, for use within async code.
+use std::borrow::Borrow;
+use std::future::Future;
+use std::ops::{Bound, RangeBounds};
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use redb::{Database, ReadTransaction, Value};
+
+use crate::{Change, Error, Model, ReadableModelTable, Subscribers, TableModel, WriteTxn};
+
+/// A job run by a worker thread.
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The maximum number of queued reads, and of queued writes.
+const QUEUE_CAPACITY: usize = 1024;
+
+/// A `redb` database running model operations on blocking threads. Each operation
+/// runs in its own transaction, and resolves to owned models, allowing it to be
+/// awaited on any async runtime without blocking the executor.
+///
+/// Reads run on a pool of threads, while writes, which `redb` runs one at a time,
+/// run on a dedicated thread, such that waiting writes never hold the threads of
+/// reads. Reads and writes are each queued up to 1024 operations, beyond which
+/// starting an operation blocks until a queued operation starts.
+///
+/// Keys and ranges are encoded as bytes before being moved to a worker thread,
+/// allowing borrowed `redb` key types to be passed by reference.
+///
+/// Writes run within a [`WriteTxn`], publishing change notifications to the
+/// subscribers of the `AsyncModelDb` and recording history as with the operations
+/// of the `WriteTxn`.
+#[derive(Clone)]
+pub struct AsyncModelDb {
+    db: Arc<Database>,
+    subscribers: Subscribers,
+    reads: SyncSender<Job>,
+    writes: SyncSender<Job>,
+}
+
+impl AsyncModelDb {
+    /// Create a new `AsyncModelDb`, running reads on a thread per available CPU.
+    pub fn new(db: Database) -> Self {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        Self::with_threads(db, threads)
+    }
+
+    /// Create a new `AsyncModelDb`, running reads on the given number of threads,
+    /// and writes on a thread of their own. The threads exit once every clone is
+    /// dropped.
+    pub fn with_threads(db: Database, threads: usize) -> Self {
+        let (reads, receiver) = mpsc::sync_channel::<Job>(QUEUE_CAPACITY);
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..threads.max(1) {
+            let receiver = receiver.clone();
+            thread::spawn(move || work(&receiver));
+        }
+        let (writes, receiver) = mpsc::sync_channel::<Job>(QUEUE_CAPACITY);
+        thread::spawn(move || work(&Mutex::new(receiver)));
+
+        Self {
+            db: Arc::new(db),
+            subscribers: Subscribers::new(),
+            reads,
+            writes,
+        }
+    }
+
+    /// Get the `redb` database.
+    pub fn database(&self) -> &Database {
+        &self.db
+    }
+
+    /// Get a receiver of the changes of `M` models made through the writes of the
+    /// `AsyncModelDb`. See `Subscribers::subscribe`.
+    pub fn subscribe<M: TableModel + Clone + Send>(&self) -> Receiver<Change<M>> {
+        self.subscribers.subscribe()
+    }
+
+    /// Run the closure on a read thread with a read transaction.
+    pub fn read<T, F>(&self, f: F) -> Blocking<Result<T, Error>>
+    where
+        T: Send + 'static,
+        F: FnOnce(&ReadTransaction) -> Result<T, Error> + Send + 'static,
+    {
+        let db = self.db.clone();
+        self.spawn(&self.reads, move || f(&db.begin_read()?))
+    }
+
+    /// Run the closure on the write thread with a write transaction, committing
+    /// the transaction if the closure succeeds, otherwise aborting it.
+    pub fn write<T, F>(&self, f: F) -> Blocking<Result<T, Error>>
+    where
+        T: Send + 'static,
+        F: FnOnce(&WriteTxn) -> Result<T, Error> + Send + 'static,
+    {
+        let (db, subscribers) = (self.db.clone(), self.subscribers.clone());
+        self.spawn(&self.writes, move || {
+            let txn = WriteTxn::with_subscribers(db.begin_write()?, &subscribers);
+            match f(&txn) {
+                Ok(value) => txn.commit().map(|_| value),
+                Err(e) => txn.abort().and(Err(e)),
+            }
+        })
+    }
+
+    /// Get the model of the given key. See `ReadableModelTable::get_model`.
+    pub fn get<'k, M: TableModel + Send>(
+        &self,
+        key: impl Borrow<<M::Key as Value>::SelfType<'k>>,
+    ) -> Blocking<Result<Option<M>, Error>> {
+        let key = encode::<M::Key>(key.borrow());
+        self.read(move |txn| {
+            let table = txn.open_table(<M as Model>::DEFINITION)?;
+            table.get_model::<M>(<M::Key as Value>::from_bytes(&key))
+        })
+    }
+
+    /// Get the models within the given key range. See
+    /// `ReadableModelTable::range_models`.
+    pub fn range<'k, M, KR>(&self, range: impl RangeBounds<KR>) -> Blocking<Result<Vec<M>, Error>>
+    where
+        M: TableModel + Send,
+        KR: Borrow<<M::Key as Value>::SelfType<'k>>,
+    {
+        let start = encode_bound::<M::Key, _>(range.start_bound());
+        let end = encode_bound::<M::Key, _>(range.end_bound());
+        self.read(move |txn| {
+            let table = txn.open_table(<M as Model>::DEFINITION)?;
+            let range = (decode_bound::<M::Key>(&start), decode_bound::<M::Key>(&end));
+            table.range_models::<M, _>(range)?.collect()
+        })
+    }
+
+    /// Insert the model, returning the replaced model, if any. See
+    /// `WriteTxn::insert`.
+    pub fn insert<M: TableModel + Clone + Send>(
+        &self,
+        mut model: M,
+    ) -> Blocking<Result<Option<M>, Error>> {
+        self.write(move |txn| txn.insert(&mut model))
+    }
+
+    /// Replace an existing model, returning the replaced model. See
+    /// `WriteTxn::update`.
+    pub fn update<M: TableModel + Clone + Send>(&self, mut model: M) -> Blocking<Result<M, Error>> {
+        self.write(move |txn| txn.update(&mut model))
+    }
+
+    /// Remove the model of the given key, returning the removed model, if any.
+    /// See `WriteTxn::remove`.
+    pub fn remove<'k, M: TableModel + Clone + Send>(
+        &self,
+        key: impl Borrow<<M::Key as Value>::SelfType<'k>>,
+    ) -> Blocking<Result<Option<M>, Error>> {
+        let key = encode::<M::Key>(key.borrow());
+        self.write(move |txn| txn.remove::<M>(<M::Key as Value>::from_bytes(&key)))
+    }
+
+    /// Queue the closure to run on the worker threads of the queue, returning a
+    /// future of the result. Blocks while the queue is full.
+    fn spawn<T, F>(&self, jobs: &SyncSender<Job>, f: F) -> Blocking<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let shared = Arc::new(Mutex::new(Shared {
+            result: None,
+            waker: None,
+        }));
+
+        let job_shared = shared.clone();
+        let job: Job = Box::new(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(f));
+            let mut shared = job_shared.lock().unwrap_or_else(PoisonError::into_inner);
+            shared.result = Some(result);
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        });
+        jobs.send(job)
+            .expect("worker threads exit only once every sender is dropped");
+
+        Blocking(shared)
+    }
+}
+
+/// Run jobs until every sender is dropped.
+fn work(receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        let job = receiver
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .recv();
+        match job {
+            Ok(job) => job(),
+            Err(_) => return,
+        }
+    }
+}
+
+/// The state shared between a `Blocking` future and the job.
+struct Shared<T> {
+    result: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+/// A future of an operation running on a blocking thread. A panic of the
+/// operation is resumed when the future is polled.
+pub struct Blocking<T>(Arc<Mutex<Shared<T>>>);
+
+impl<T> Future for Blocking<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        match shared.result.take() {
+            Some(Ok(value)) => Poll::Ready(value),
+            Some(Err(panic)) => panic::resume_unwind(panic),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Encode the key as bytes.
+fn encode<K: Value>(key: &K::SelfType<'_>) -> Vec<u8> {
+    K::as_bytes(key).as_ref().to_vec()
+}
+
+/// Encode the key of the bound as bytes.
+fn encode_bound<'k, K, KR>(bound: Bound<&KR>) -> Bound<Vec<u8>>
+where
+    K: Value + 'k,
+    KR: Borrow<K::SelfType<'k>>,
+{
+    match bound {
+        Bound::Included(key) => Bound::Included(encode::<K>(key.borrow())),
+        Bound::Excluded(key) => Bound::Excluded(encode::<K>(key.borrow())),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+/// Decode the key of the bound from bytes.
+fn decode_bound<K: Value>(bound: &Bound<Vec<u8>>) -> Bound<K::SelfType<'_>> {
+    match bound {
+        Bound::Included(bytes) => Bound::Included(K::from_bytes(bytes)),
+        Bound::Excluded(bytes) => Bound::Excluded(K::from_bytes(bytes)),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
@@ -0,0 +1,22 @@
+//! Inspect a `redb` database, printing tables, registered model schemas and rows.
+use std::env;
+use std::io;
+use std::process::ExitCode;
+
+use redb_model::inspect::{Inspector, USAGE};
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    if args.is_empty() || args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    match Inspector::new().run(args, &mut io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Clocks for `created_at` and `updated_at` fields.
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A source of the current time.
+pub trait Clock {
+    /// Get the current time.
+    fn now(&self) -> SystemTime;
+}
+
+/// A `Clock` of the system time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A type declared as a `created_at` or `updated_at` field.
+pub trait Timestamp: Clone {
+    /// Instantiate from the given time.
+    fn from_system_time(time: SystemTime) -> Self;
+}
+
+impl Timestamp for SystemTime {
+    fn from_system_time(time: SystemTime) -> Self {
+        time
+    }
+}
+
+/// The duration since the unix epoch. Times before the epoch are saturated.
+impl Timestamp for Duration {
+    fn from_system_time(time: SystemTime) -> Self {
+        time.duration_since(UNIX_EPOCH).unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Timestamp for chrono::DateTime<chrono::Utc> {
+    fn from_system_time(time: SystemTime) -> Self {
+        time.into()
+    }
+}
+
+#[cfg(feature = "time")]
+impl Timestamp for time::OffsetDateTime {
+    fn from_system_time(time: SystemTime) -> Self {
+        time.into()
+    }
+}
@@ -0,0 +1,289 @@
+//! Model operations on a `redb` database.
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+use std::thread;
+use std::time::Duration;
+
+use redb::{
+    Database, Key, MultimapTableDefinition, StorageBackend, TableDefinition, Value,
+    WriteTransaction,
+};
+
+use crate::{schema, Change, Error, Model, Subscribers, TableModel, WriteTxn};
+
+/// Create the table of a model, and check and register the schema.
+type Bootstrap = fn(&WriteTransaction) -> Result<(), Error>;
+
+/// A `redb` database with the tables of all registered models created, and their
+/// key and value types validated when opened. Dereferences to the
+/// `redb::Database`.
+///
+/// Write transactions of [`DatabaseExt`] publish their changes to the subscribers
+/// of the database.
+pub struct ModelDatabase {
+    db: Database,
+    subscribers: Subscribers,
+}
+
+impl ModelDatabase {
+    /// Create a `ModelDatabaseBuilder`.
+    pub fn builder() -> ModelDatabaseBuilder {
+        ModelDatabaseBuilder::new()
+    }
+
+    /// Get a receiver of the changes of `M` models made through the write
+    /// transactions of the database. See `Subscribers::subscribe`.
+    pub fn subscribe<M: TableModel + Clone + Send>(&self) -> Receiver<Change<M>> {
+        self.subscribers.subscribe()
+    }
+
+    /// Get the subscribers of the database, such as to create a `WriteTxn` with
+    /// `WriteTxn::with_subscribers`.
+    pub fn subscribers(&self) -> &Subscribers {
+        &self.subscribers
+    }
+
+    /// Get the `redb` database.
+    pub fn into_inner(self) -> Database {
+        self.db
+    }
+}
+
+impl Deref for ModelDatabase {
+    type Target = Database;
+
+    fn deref(&self) -> &Self::Target {
+        &self.db
+    }
+}
+
+/// A builder of a `ModelDatabase`, registering the models of the database.
+pub struct ModelDatabaseBuilder {
+    builder: redb::Builder,
+    models: Vec<Bootstrap>,
+}
+
+impl ModelDatabaseBuilder {
+    /// Create a builder without any registered models.
+    pub fn new() -> Self {
+        Self {
+            builder: Database::builder(),
+            models: Vec::new(),
+        }
+    }
+
+    /// Set the `redb` builder opening the database.
+    pub fn redb_builder(mut self, builder: redb::Builder) -> Self {
+        self.builder = builder;
+        self
+    }
+
+    /// Register the model `M`, creating the table when the database is opened.
+    pub fn register<M>(mut self) -> Self
+    where
+        M: for<'a> Model<'a>,
+        for<'a> <M as Model<'a>>::TableType: ModelDefinition,
+    {
+        self.models.push(|txn| {
+            M::DEFINITION.create(txn)?;
+            schema::check_and_register::<M>(txn)
+        });
+        self
+    }
+
+    /// Open the database at the path, creating the file if it does not exist.
+    /// Creates the table of each registered model, and registers their schema.
+    /// Returns an error if an existing table differs in type from the model, or
+    /// `Error::SchemaMismatch` if the registered schema differs. See
+    /// `schema::check`.
+    pub fn open(self, path: impl AsRef<Path>) -> Result<ModelDatabase, Error> {
+        let db = self.builder.create(path)?;
+        bootstrap(db, &self.models)
+    }
+
+    /// Open the database with the given storage backend. See `open`.
+    pub fn create_with_backend(self, backend: impl StorageBackend) -> Result<ModelDatabase, Error> {
+        let db = self.builder.create_with_backend(backend)?;
+        bootstrap(db, &self.models)
+    }
+}
+
+impl Default for ModelDatabaseBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Create the tables of the models in a single transaction.
+fn bootstrap(db: Database, models: &[Bootstrap]) -> Result<ModelDatabase, Error> {
+    let txn = db.begin_write()?;
+    for model in models {
+        model(&txn)?;
+    }
+    txn.commit()?;
+
+    Ok(ModelDatabase {
+        db,
+        subscribers: Subscribers::new(),
+    })
+}
+
+/// A table definition of a model, created by a `ModelDatabase`.
+pub trait ModelDefinition {
+    /// Create the table, if it does not exist. Returns an error if the table
+    /// exists with a different type.
+    fn create(&self, txn: &WriteTransaction) -> Result<(), Error>;
+}
+
+impl<K: Key + 'static, V: Value + 'static> ModelDefinition for TableDefinition<'_, K, V> {
+    fn create(&self, txn: &WriteTransaction) -> Result<(), Error> {
+        txn.open_table(*self)?;
+        Ok(())
+    }
+}
+
+impl<K: Key + 'static, V: Key + 'static> ModelDefinition for MultimapTableDefinition<'_, K, V> {
+    fn create(&self, txn: &WriteTransaction) -> Result<(), Error> {
+        txn.open_multimap_table(*self)?;
+        Ok(())
+    }
+}
+
+/// The policy of retrying a failed write transaction. By default, a transaction
+/// is attempted up to 3 times, while the error is `Error::is_transient`, waiting
+/// 10ms before the first retry and doubling the wait up to 1s.
+#[derive(Debug, Clone, Copy)]
+pub struct Retry {
+    attempts: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+    retry_if: fn(&Error) -> bool,
+}
+
+impl Retry {
+    /// Create the default retry policy.
+    pub fn new() -> Self {
+        Self {
+            attempts: 3,
+            backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+            retry_if: Error::is_transient,
+        }
+    }
+
+    /// A policy attempting a transaction once, without retrying.
+    pub fn never() -> Self {
+        Self::new().attempts(1)
+    }
+
+    /// Set the maximum number of attempts, including the first. At least one
+    /// attempt is always made.
+    pub fn attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts.max(1);
+        self
+    }
+
+    /// Set the wait before the first retry, doubled for each further retry.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Set the maximum wait between retries.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Set the function deciding whether an error is retried.
+    pub fn retry_if(mut self, retry_if: fn(&Error) -> bool) -> Self {
+        self.retry_if = retry_if;
+        self
+    }
+
+    /// Run the function until it succeeds, returns an error that is not retried,
+    /// or the attempts are exhausted, returning the last result.
+    fn run<T>(&self, mut f: impl FnMut() -> Result<T, Error>) -> Result<T, Error> {
+        let mut backoff = self.backoff;
+        for _ in 1..self.attempts {
+            match f() {
+                Err(e) if (self.retry_if)(&e) => {
+                    thread::sleep(backoff);
+                    backoff = backoff.saturating_mul(2).min(self.max_backoff);
+                }
+                result => return result,
+            }
+        }
+
+        f()
+    }
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Model transactions on a `redb` database.
+pub trait DatabaseExt {
+    /// Run the closure with a write transaction, committing the transaction if
+    /// the closure succeeds, otherwise aborting it. Retries transient errors of
+    /// the closure or commit with the default `Retry` policy.
+    ///
+    /// The closure is run again for each retry, and should not depend on the
+    /// state of a previous attempt.
+    fn write<T>(&self, f: impl FnMut(&WriteTxn) -> Result<T, Error>) -> Result<T, Error> {
+        self.write_with(&Retry::default(), f)
+    }
+
+    /// Run the closure with a write transaction, retrying with the given `Retry`
+    /// policy. See `DatabaseExt::write`.
+    fn write_with<T>(
+        &self,
+        retry: &Retry,
+        f: impl FnMut(&WriteTxn) -> Result<T, Error>,
+    ) -> Result<T, Error>;
+}
+
+impl DatabaseExt for Database {
+    fn write_with<T>(
+        &self,
+        retry: &Retry,
+        f: impl FnMut(&WriteTxn) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        write_with(self, &Subscribers::new(), retry, f)
+    }
+}
+
+impl DatabaseExt for ModelDatabase {
+    fn write_with<T>(
+        &self,
+        retry: &Retry,
+        f: impl FnMut(&WriteTxn) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        write_with(&self.db, &self.subscribers, retry, f)
+    }
+}
+
+/// Run the closure with a write transaction publishing to the subscribers. See
+/// `DatabaseExt::write_with`.
+fn write_with<T>(
+    db: &Database,
+    subscribers: &Subscribers,
+    retry: &Retry,
+    mut f: impl FnMut(&WriteTxn) -> Result<T, Error>,
+) -> Result<T, Error> {
+    retry.run(|| {
+        let txn = WriteTxn::with_subscribers(db.begin_write()?, subscribers);
+        match f(&txn) {
+            Ok(value) => txn.commit().map(|_| value),
+            Err(e) => {
+                // The error of the closure is returned, even if the abort fails.
+                let _ = txn.abort();
+                Err(e)
+            }
+        }
+    })
+}
@@ -0,0 +1,165 @@
+//! Errors of model operations.
+use std::{fmt, io};
+
+use crate::ValidationError;
+
+/// An error of a model operation on a `redb` database.
+#[derive(Debug)]
+pub enum Error {
+    /// An error returned by `redb`.
+    Redb(Box<redb::Error>),
+    /// A model rejected by a `validate` function.
+    Validation(ValidationError),
+    /// No model exists for the key of an updated model.
+    NotFound,
+    /// An I/O error of an export or import.
+    Io(io::Error),
+    /// A JSON error of an export or import.
+    #[cfg(feature = "serde")]
+    Json(serde_json::Error),
+    /// An invalid CSV record of an import.
+    Csv {
+        /// The line number of the record.
+        line: u64,
+        /// The error message.
+        message: String,
+    },
+    /// The stored version of an updated model differs from the model version.
+    Conflict {
+        /// The version of the updated model.
+        expected: u64,
+        /// The version of the stored model.
+        found: u64,
+    },
+    /// The schema of a model differs from the schema registered for the table.
+    SchemaMismatch {
+        /// The table name.
+        table: String,
+        /// The schema hash of the model.
+        expected: u64,
+        /// The schema hash registered for the table.
+        found: u64,
+    },
+    /// A write of a model declaring `history` made directly on the table, which
+    /// would not record the history of the model. Such models are written with
+    /// a `WriteTxn`.
+    HistoryNotRecorded {
+        /// The history table name.
+        history: &'static str,
+    },
+}
+
+impl Error {
+    /// Whether the error may not recur when the transaction is retried. Includes
+    /// version conflicts, and interrupted or timed out I/O.
+    pub fn is_transient(&self) -> bool {
+        fn is_transient_io(e: &io::Error) -> bool {
+            matches!(
+                e.kind(),
+                io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+            )
+        }
+
+        match self {
+            Error::Conflict { .. } => true,
+            Error::Io(e) => is_transient_io(e),
+            Error::Redb(e) => matches!(e.as_ref(), redb::Error::Io(e) if is_transient_io(e)),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Redb(e) => e.fmt(f),
+            Error::Validation(e) => e.fmt(f),
+            Error::Io(e) => e.fmt(f),
+            #[cfg(feature = "serde")]
+            Error::Json(e) => e.fmt(f),
+            Error::Csv { line, message } => {
+                write!(f, "invalid CSV record on line {}: {}", line, message)
+            }
+            Error::NotFound => write!(f, "model not found"),
+            Error::Conflict { expected, found } => write!(
+                f,
+                "model version conflict: expected version {}, found {}",
+                expected, found
+            ),
+            Error::SchemaMismatch {
+                table,
+                expected,
+                found,
+            } => write!(
+                f,
+                "schema mismatch of table `{}`: expected hash {:016x}, found {:016x}",
+                table, expected, found
+            ),
+            Error::HistoryNotRecorded { history } => write!(
+                f,
+                "history not recorded by a table write: models of history `{}` are written with a `WriteTxn`",
+                history
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Redb(e) => Some(e.as_ref()),
+            Error::Validation(e) => Some(e),
+            Error::Io(e) => Some(e),
+            #[cfg(feature = "serde")]
+            Error::Json(e) => Some(e),
+            Error::Csv { .. }
+            | Error::NotFound
+            | Error::Conflict { .. }
+            | Error::SchemaMismatch { .. }
+            | Error::HistoryNotRecorded { .. } => None,
+        }
+    }
+}
+
+impl From<ValidationError> for Error {
+    fn from(e: ValidationError) -> Self {
+        Error::Validation(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+/// Implement `From<E>` for `redb` errors.
+macro_rules! impl_from_redb {
+    (
+        $( $error:ty ), *
+    ) => {
+        $(
+            impl From<$error> for Error {
+                fn from(e: $error) -> Self {
+                    Error::Redb(Box::new(e.into()))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_redb!(
+    redb::Error,
+    redb::StorageError,
+    redb::TableError,
+    redb::TransactionError,
+    redb::CommitError,
+    redb::DatabaseError
+);
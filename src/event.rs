@@ -0,0 +1,245 @@
+//! Event sourced aggregates, stored as events keyed by aggregate and sequence.
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+
+use redb::{Key, ReadTransaction, ReadableTable, TableError, Value};
+
+use crate::{Error, Model, ReadableModelTable, TableModel, WriteTxn};
+
+/// An aggregate rebuilt by folding its events, in sequence order. A new aggregate
+/// is created with `Default` before any event is applied.
+pub trait Aggregate<E>: Default {
+    /// Apply the event to the aggregate.
+    fn apply(&mut self, event: &E);
+}
+
+/// A store of the `E` events of `S` aggregates. Events are models keyed by the
+/// aggregate id and a sequence number, with the sequence number of the last
+/// event being the version of the aggregate.
+///
+/// The key fields of appended events are replaced by the aggregate id and the
+/// next sequence number. Aggregates declaring a `version` field may be stored as
+/// snapshots within the table of `S`, such that only later events are replayed.
+///
+/// Events and snapshots are written within a [`WriteTxn`], recording their
+/// history and changes as with the operations of the `WriteTxn`.
+pub struct EventStore<E, S> {
+    snapshot_every: u64,
+    _model: PhantomData<(E, S)>,
+}
+
+impl<I, E, S> EventStore<E, S>
+where
+    I: Key + 'static,
+    E: TableModel<Key = (I, u64)>,
+    S: TableModel<Key = I> + Aggregate<E>,
+{
+    /// Create an `EventStore` without snapshots.
+    pub fn new() -> Self {
+        Self {
+            snapshot_every: 0,
+            _model: PhantomData,
+        }
+    }
+
+    /// Store a snapshot of the aggregate each time an append reaches or passes a
+    /// multiple of the given number of events. Disabled by `0`.
+    pub fn snapshot_every(mut self, events: u64) -> Self {
+        self.snapshot_every = events;
+        self
+    }
+
+    /// Get the version of the aggregate of the given id, or `0` if no event
+    /// exists.
+    pub fn version<'k>(
+        &self,
+        txn: &ReadTransaction,
+        id: impl Borrow<I::SelfType<'k>>,
+    ) -> Result<u64, Error> {
+        match txn.open_table(<E as Model>::DEFINITION) {
+            Ok(events) => last_seq(&events, I::as_bytes(id.borrow()).as_ref()),
+            Err(TableError::TableDoesNotExist(_)) => Ok(0),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Append the events to the aggregate of the given id, returning the new
+    /// version of the aggregate. Returns `Error::Conflict` if the version of the
+    /// aggregate differs from the expected version.
+    pub fn append<'k>(
+        &self,
+        txn: &WriteTxn,
+        id: impl Borrow<I::SelfType<'k>>,
+        expected_version: u64,
+        events: impl IntoIterator<Item = E>,
+    ) -> Result<u64, Error>
+    where
+        E: Clone + Send,
+        S: Clone + Send,
+    {
+        let id = I::as_bytes(id.borrow());
+        let id = id.as_ref();
+
+        let mut table = txn.table::<E>()?;
+        let found = last_seq(&*table, id)?;
+        if found != expected_version {
+            return Err(Error::Conflict {
+                expected: expected_version,
+                found,
+            });
+        }
+
+        let mut version = expected_version;
+        for mut event in events {
+            event.before_insert();
+            event.validate()?;
+            version += 1;
+            table.insert((I::from_bytes(id), version), event.as_value())?;
+            let event = E::from_values(((I::from_bytes(id), version), event.as_value()));
+            txn.inserted(&event, None)?;
+        }
+        drop(table);
+
+        let every = self.snapshot_every;
+        if every > 0 && version / every > expected_version / every {
+            self.snapshot(txn, I::from_bytes(id))?;
+        }
+
+        Ok(version)
+    }
+
+    /// Get the events of the aggregate of the given id after the given version,
+    /// in sequence order.
+    pub fn events<'k>(
+        &self,
+        txn: &ReadTransaction,
+        id: impl Borrow<I::SelfType<'k>>,
+        after: u64,
+    ) -> Result<Vec<E>, Error> {
+        match txn.open_table(<E as Model>::DEFINITION) {
+            Ok(events) => events_after(&events, I::as_bytes(id.borrow()).as_ref(), after),
+            Err(TableError::TableDoesNotExist(_)) => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Load the aggregate of the given id from the latest snapshot, if any, and
+    /// the later events, returning the aggregate and version.
+    pub fn load<'k>(
+        &self,
+        txn: &ReadTransaction,
+        id: impl Borrow<I::SelfType<'k>>,
+    ) -> Result<(S, u64), Error> {
+        let id = I::as_bytes(id.borrow());
+        let snapshot = match txn.open_table(<S as Model>::DEFINITION) {
+            Ok(table) => table.get_model::<S>(I::from_bytes(id.as_ref()))?,
+            Err(TableError::TableDoesNotExist(_)) => None,
+            Err(e) => return Err(e.into()),
+        };
+        let events = match txn.open_table(<E as Model>::DEFINITION) {
+            Ok(table) => Some(table),
+            Err(TableError::TableDoesNotExist(_)) => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        replay::<I, E, S>(snapshot, events.as_ref(), id.as_ref())
+    }
+
+    /// Store a snapshot of the aggregate of the given id, returning the version
+    /// of the snapshot. Has no effect on aggregates without a `version` field.
+    pub fn snapshot<'k>(
+        &self,
+        txn: &WriteTxn,
+        id: impl Borrow<I::SelfType<'k>>,
+    ) -> Result<u64, Error>
+    where
+        S: Clone + Send,
+    {
+        let id = I::as_bytes(id.borrow());
+        let id = id.as_ref();
+
+        let snapshot = txn.get::<S>(I::from_bytes(id))?;
+        let (aggregate, version) = {
+            let events = txn.table::<E>()?;
+            replay::<I, E, S>(snapshot, Some(&*events), id)?
+        };
+        if aggregate.version().is_some() {
+            txn.replace(&aggregate)?;
+        }
+
+        Ok(version)
+    }
+}
+
+impl<I, E, S> Default for EventStore<E, S>
+where
+    I: Key + 'static,
+    E: TableModel<Key = (I, u64)>,
+    S: TableModel<Key = I> + Aggregate<E>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get the sequence number of the last event of the aggregate, or `0`.
+fn last_seq<I: Key + 'static, V: Value + 'static>(
+    events: &impl ReadableTable<(I, u64), V>,
+    id: &[u8],
+) -> Result<u64, Error> {
+    let last = events
+        .range((I::from_bytes(id), 0)..=(I::from_bytes(id), u64::MAX))?
+        .next_back()
+        .transpose()?;
+
+    Ok(last.map(|(k, _)| k.value().1).unwrap_or(0))
+}
+
+/// Get the events of the aggregate after the given sequence number.
+fn events_after<I, E>(
+    events: &impl ReadableTable<(I, u64), E::Value>,
+    id: &[u8],
+    after: u64,
+) -> Result<Vec<E>, Error>
+where
+    I: Key + 'static,
+    E: TableModel<Key = (I, u64)>,
+{
+    let start = (I::from_bytes(id), after.saturating_add(1));
+    let end = (I::from_bytes(id), u64::MAX);
+
+    events.range_models::<E, _>(start..=end)?.collect()
+}
+
+/// Load the aggregate from the snapshot, if any, and apply the later events.
+fn replay<I, E, S>(
+    snapshot: Option<S>,
+    events: Option<&impl ReadableTable<(I, u64), E::Value>>,
+    id: &[u8],
+) -> Result<(S, u64), Error>
+where
+    I: Key + 'static,
+    E: TableModel<Key = (I, u64)>,
+    S: TableModel<Key = I> + Aggregate<E>,
+{
+    let (mut aggregate, mut version) = match snapshot {
+        Some(snapshot) => match snapshot.version() {
+            Some(version) => (snapshot, version),
+            None => (S::default(), 0),
+        },
+        None => (S::default(), 0),
+    };
+
+    if let Some(events) = events {
+        for event in events_after::<I, E>(events, id, version)? {
+            aggregate.apply(&event);
+            version = event.as_key().1;
+        }
+    }
+
+    // Set the key fields of the aggregate to the aggregate id.
+    let mut aggregate = S::from_values((I::from_bytes(id), aggregate.as_value()));
+    aggregate.set_version(version);
+
+    Ok((aggregate, version))
+}
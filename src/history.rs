@@ -0,0 +1,142 @@
+//! The history of models declaring `history`, recorded by a
+//! [`WriteTxn`](crate::WriteTxn).
+//!
+//! Each insertion, update or removal of a model through a `WriteTxn`, an import
+//! or an `EventStore` appends the previous value of the model to the history
+//! table, keyed by the model key and a sequence number. Sequence numbers increase
+//! for each change to the table, across all keys. Writes with `ModelTable` return
+//! `Error::HistoryNotRecorded`, while changes made directly on the `redb` table
+//! are not recorded.
+use std::borrow::Borrow;
+
+use redb::{
+    ReadOnlyTable, ReadTransaction, ReadableTable, TableDefinition, TableError, Value,
+    WriteTransaction,
+};
+
+use crate::{Error, Model, ReadableModelTable, TableModel};
+
+/// The last sequence number of each history table, keyed by table name.
+pub const SEQUENCE_TABLE: TableDefinition<&str, u64> =
+    TableDefinition::new("redb_model_history_sequence");
+
+/// The history table of `M`, keyed by the model key and sequence number, holding
+/// the previous value, or `None` if the model did not exist.
+type HistoryDefinition<'h, M> =
+    TableDefinition<'h, (<M as TableModel>::Key, u64), Option<<M as TableModel>::Value>>;
+
+/// The read only history table of `M`.
+type HistoryTable<M> =
+    ReadOnlyTable<(<M as TableModel>::Key, u64), Option<<M as TableModel>::Value>>;
+
+/// A change to a model recorded in the history table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Revision<M> {
+    /// The sequence number of the change.
+    pub seq: u64,
+    /// The model replaced or removed by the change, or `None` if the model was
+    /// inserted.
+    pub previous: Option<M>,
+}
+
+/// Get the last sequence number of the history of `M`, or `0` if no change has
+/// been recorded.
+pub fn sequence<M: TableModel>(txn: &ReadTransaction) -> Result<u64, Error> {
+    let Some(name) = M::HISTORY else {
+        return Ok(0);
+    };
+    let table = match txn.open_table(SEQUENCE_TABLE) {
+        Ok(table) => table,
+        Err(TableError::TableDoesNotExist(_)) => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(table.get(name)?.map(|guard| guard.value()).unwrap_or(0))
+}
+
+/// Get the recorded changes to the model of the given key, ordered by sequence
+/// number.
+pub fn history<'k, M: TableModel>(
+    txn: &ReadTransaction,
+    key: impl Borrow<<M::Key as Value>::SelfType<'k>>,
+) -> Result<Vec<Revision<M>>, Error> {
+    let Some(table) = open::<M>(txn)? else {
+        return Ok(Vec::new());
+    };
+    let bytes = M::Key::as_bytes(key.borrow());
+    let start = (M::Key::from_bytes(bytes.as_ref()), 0);
+    let end = (M::Key::from_bytes(bytes.as_ref()), u64::MAX);
+
+    table
+        .range(start..=end)?
+        .map(|entry| {
+            let (k, v) = entry?;
+            let (key, seq) = k.value();
+            let previous = v.value().map(|value| M::from_values((key, value)));
+
+            Ok(Revision { seq, previous })
+        })
+        .collect()
+}
+
+/// Get the model of the given key as of the given sequence number, after all
+/// changes up to and including the sequence number. Models changed before their
+/// history was recorded are returned as currently stored.
+pub fn as_of<'k, M: TableModel>(
+    txn: &ReadTransaction,
+    key: impl Borrow<<M::Key as Value>::SelfType<'k>>,
+    seq: u64,
+) -> Result<Option<M>, Error> {
+    let key = key.borrow();
+    if let Some(table) = open::<M>(txn)? {
+        let bytes = M::Key::as_bytes(key);
+        let start = (M::Key::from_bytes(bytes.as_ref()), seq.saturating_add(1));
+        let end = (M::Key::from_bytes(bytes.as_ref()), u64::MAX);
+
+        // The first later change replaced the model as of the sequence number.
+        if let Some(entry) = table.range(start..=end)?.next() {
+            let (k, v) = entry?;
+            let (key, _) = k.value();
+            return Ok(v.value().map(|value| M::from_values((key, value))));
+        }
+    }
+
+    txn.open_table(<M as Model>::DEFINITION)?.get_model(key)
+}
+
+/// Append the model replaced or removed by a change to the model, if any, to
+/// the history of `M`, if declared.
+pub(crate) fn record<M: TableModel>(
+    txn: &WriteTransaction,
+    model: &M,
+    previous: Option<&M>,
+) -> Result<(), Error> {
+    let Some(name) = M::HISTORY else {
+        return Ok(());
+    };
+
+    let mut sequence = txn.open_table(SEQUENCE_TABLE)?;
+    let seq = sequence.get(name)?.map(|guard| guard.value()).unwrap_or(0) + 1;
+    sequence.insert(name, seq)?;
+
+    let mut table = txn.open_table(HistoryDefinition::<M>::new(name))?;
+    table.insert(
+        (model.as_key(), seq),
+        previous.map(|model| model.as_value()),
+    )?;
+
+    Ok(())
+}
+
+/// Open the history table of `M`, if declared and created.
+fn open<M: TableModel>(txn: &ReadTransaction) -> Result<Option<HistoryTable<M>>, Error> {
+    let Some(name) = M::HISTORY else {
+        return Ok(None);
+    };
+
+    match txn.open_table(HistoryDefinition::<M>::new(name)) {
+        Ok(table) => Ok(Some(table)),
+        Err(TableError::TableDoesNotExist(_)) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,432 @@
+//! Inspection of a database, printing tables, schemas and rows.
+mod raw;
+
+use std::collections::BTreeMap;
+use std::error;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::ops::Bound;
+
+use redb::{
+    Database, MultimapTableHandle, ReadTransaction, ReadableTableMetadata, TableHandle, Value,
+};
+
+use self::raw::Cell;
+use crate::schema::{self, TableSchema};
+use crate::transfer::parse_record;
+use crate::{CsvModel, Error, Model, ReadableModelTable, TableModel};
+
+/// A header, and the rows of cells printed by a command.
+type Listing = (Vec<String>, Vec<Vec<Cell>>);
+
+/// The usage of the inspection command line.
+pub const USAGE: &str = "\
+usage: redb-model <path> <command> [options]
+
+commands:
+    tables              list the tables, row counts and registered key/value types
+    schema <table>      print the registered schema of the table
+    rows <table>        print the rows of the table, if the schema is registered
+
+options:
+    --json              print JSON, rather than a table
+    --from <key>        print rows from the key, inclusive
+    --to <key>          print rows to the key, exclusive
+    --limit <n>         print at most `n` rows
+
+Composite keys are given as comma separated fields, in key order.";
+
+/// An inspector of a database, printing the tables, the schemas registered with
+/// `schema::register`, and the rows of the tables.
+///
+/// Rows of a registered model are printed converting fields with [`CsvModel`].
+/// Rows of other tables are decoded by the `redb` types stored within the table,
+/// supporting primitive types, `&str`, `String`, `&[u8]`, `Option`, arrays, `Vec`
+/// and tuples of these, as with the `redb-model` binary, which registers no models.
+/// Values of other types, such as those of adapters, are printed as hexadecimal.
+#[derive(Default)]
+pub struct Inspector {
+    models: BTreeMap<&'static str, Box<dyn Inspect>>,
+}
+
+impl Inspector {
+    /// Create a new `Inspector` without any registered models.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `M`, allowing the rows of the table to be printed.
+    pub fn register<M: TableModel + CsvModel>(mut self) -> Self {
+        let schema = <M as Model>::SCHEMA;
+        self.models
+            .insert(schema.name, Box::new(Registered::<M>(PhantomData)));
+        self
+    }
+
+    /// Open the database at the path of the first argument, and run the command
+    /// of the remaining arguments. See [`USAGE`].
+    pub fn run(
+        &self,
+        args: impl IntoIterator<Item = String>,
+        out: &mut impl Write,
+    ) -> Result<(), Box<dyn error::Error>> {
+        let mut args = args.into_iter();
+        let path = args.next().ok_or("missing database path")?;
+        let db = Database::open(path)?;
+
+        self.inspect(&db, args, out)
+    }
+
+    /// Run the command of the arguments on the database. See [`USAGE`].
+    pub fn inspect(
+        &self,
+        db: &Database,
+        args: impl IntoIterator<Item = String>,
+        out: &mut impl Write,
+    ) -> Result<(), Box<dyn error::Error>> {
+        let args = Args::parse(args)?;
+        let txn = db.begin_read()?;
+
+        let (header, rows) = match (args.command.as_str(), &args.table) {
+            ("tables", None) => self.tables(&txn)?,
+            ("schema", Some(table)) => self.schema(&txn, table)?,
+            ("rows", Some(table)) => self.rows(&txn, table, &args)?,
+            ("tables", Some(_)) => return Err("unexpected table argument".into()),
+            ("schema" | "rows", None) => return Err("missing table argument".into()),
+            (command, _) => return Err(format!("unknown command `{}`", command).into()),
+        };
+
+        match args.json {
+            true => write_json(out, &header, &rows)?,
+            false => write_table(out, &header, &rows)?,
+        }
+        Ok(())
+    }
+
+    /// List the tables, row counts and registered key/value types.
+    fn tables(&self, txn: &ReadTransaction) -> Result<Listing, Error> {
+        let header = ["table", "type", "rows", "key", "value"];
+        let schemas = schema::all(txn)?
+            .into_iter()
+            .map(|schema| (schema.name.clone(), schema))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut rows = Vec::new();
+        for handle in txn.list_tables()? {
+            if handle.name() == schema::SCHEMA_TABLE.name() {
+                continue;
+            }
+            let len = txn.open_untyped_table(handle.clone())?.len()?;
+            rows.push(table_row(
+                handle.name(),
+                "table",
+                len,
+                schemas.get(handle.name()),
+            ));
+        }
+        for handle in txn.list_multimap_tables()? {
+            let len = txn.open_untyped_multimap_table(handle.clone())?.len()?;
+            rows.push(table_row(
+                handle.name(),
+                "multimap",
+                len,
+                schemas.get(handle.name()),
+            ));
+        }
+        rows.sort_by_key(|row| row[0].to_string());
+
+        Ok((header.map(String::from).to_vec(), rows))
+    }
+
+    /// Print the registered schema of the table, or the schema of the registered
+    /// model if the schema is not registered within the database.
+    fn schema(&self, txn: &ReadTransaction, table: &str) -> Result<Listing, Box<dyn error::Error>> {
+        let header = ["field", "position", "type", "redb_type"];
+        let schema = match schema::get(txn, table)? {
+            Some(schema) => schema,
+            None => match self.models.get(table) {
+                Some(model) => model.schema(),
+                None => return Err(format!("no schema is registered for `{}`", table).into()),
+            },
+        };
+
+        let rows = schema
+            .fields
+            .into_iter()
+            .map(|field| {
+                let position = if field.key { "key" } else { "value" };
+                [field.name, position.to_owned(), field.ty, field.redb_type]
+                    .map(Cell::Str)
+                    .to_vec()
+            })
+            .collect();
+        Ok((header.map(String::from).to_vec(), rows))
+    }
+
+    /// Print the rows of the registered model of the table, or the rows decoded
+    /// by the `redb` types stored within the table if the model is not registered.
+    fn rows(
+        &self,
+        txn: &ReadTransaction,
+        table: &str,
+        args: &Args,
+    ) -> Result<Listing, Box<dyn error::Error>> {
+        let layout;
+        let model = match self.models.get(table) {
+            Some(model) => model.as_ref(),
+            None => {
+                let schema = schema::get(txn, table)?
+                    .ok_or_else(|| format!("no schema is registered for `{}`", table))?;
+                layout = raw::Layout::open(txn, schema)?;
+                &layout as &dyn Inspect
+            }
+        };
+
+        let key_bytes = |key: &Option<String>| -> Result<Option<Vec<u8>>, String> {
+            key.as_deref()
+                .map(|key| {
+                    let record = parse_record(key, ',');
+                    let record = record.iter().map(String::as_str).collect::<Vec<_>>();
+                    model.key_bytes(&record)
+                })
+                .transpose()
+        };
+        let from = key_bytes(&args.from)?;
+        let to = key_bytes(&args.to)?;
+
+        let header = model.schema().fields.into_iter().map(|f| f.name);
+        let rows = model.rows(txn, from, to, args.limit)?;
+        Ok((header.collect(), rows))
+    }
+}
+
+/// The arguments of a command.
+struct Args {
+    command: String,
+    table: Option<String>,
+    json: bool,
+    from: Option<String>,
+    to: Option<String>,
+    limit: Option<usize>,
+}
+
+impl Args {
+    /// Parse the command, table and options.
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, Box<dyn error::Error>> {
+        let mut positional = Vec::new();
+        let mut json = false;
+        let (mut from, mut to, mut limit) = (None, None, None);
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("missing value of `{}`", arg));
+            match arg.as_str() {
+                "--json" => json = true,
+                "--from" => from = Some(value()?),
+                "--to" => to = Some(value()?),
+                "--limit" => limit = Some(value()?.parse()?),
+                _ if arg.starts_with("--") => {
+                    return Err(format!("unknown option `{}`", arg).into())
+                }
+                _ => positional.push(arg),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        let command = positional.next().ok_or("missing command")?;
+        let table = positional.next();
+        if let Some(arg) = positional.next() {
+            return Err(format!("unexpected argument `{}`", arg).into());
+        }
+
+        Ok(Self {
+            command,
+            table,
+            json,
+            from,
+            to,
+            limit,
+        })
+    }
+}
+
+/// Get the row of a table, and the registered key/value types, if any.
+fn table_row(name: &str, ty: &str, len: u64, schema: Option<&TableSchema>) -> Vec<Cell> {
+    let (key, value) = match schema {
+        Some(schema) => (schema.key_type.clone(), schema.value_type.clone()),
+        None => (String::from("-"), String::from("-")),
+    };
+
+    vec![
+        Cell::Str(name.to_owned()),
+        Cell::Str(ty.to_owned()),
+        Cell::UInt(len.into()),
+        Cell::Str(key),
+        Cell::Str(value),
+    ]
+}
+
+/// Operations on the rows of a table.
+trait Inspect {
+    /// Get the schema of the model.
+    fn schema(&self) -> TableSchema;
+
+    /// Get the encoded key of a record of the key fields.
+    fn key_bytes(&self, record: &[&str]) -> Result<Vec<u8>, String>;
+
+    /// Get the records of the models within the encoded key range.
+    fn rows(
+        &self,
+        txn: &ReadTransaction,
+        from: Option<Vec<u8>>,
+        to: Option<Vec<u8>>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Vec<Cell>>, Box<dyn error::Error>>;
+}
+
+/// A registered model.
+struct Registered<M>(PhantomData<fn() -> M>);
+
+impl<M: TableModel + CsvModel> Inspect for Registered<M> {
+    fn schema(&self) -> TableSchema {
+        <M as Model>::SCHEMA.into()
+    }
+
+    fn key_bytes(&self, record: &[&str]) -> Result<Vec<u8>, String> {
+        let len = M::FIELDS.iter().filter(|field| field.key).count();
+        if record.len() != len {
+            return Err(format!(
+                "expected {} key fields, found {}",
+                len,
+                record.len()
+            ));
+        }
+
+        M::key_bytes(record)
+    }
+
+    fn rows(
+        &self,
+        txn: &ReadTransaction,
+        from: Option<Vec<u8>>,
+        to: Option<Vec<u8>>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Vec<Cell>>, Box<dyn error::Error>> {
+        let table = txn.open_table(<M as Model>::DEFINITION)?;
+        let range = (
+            from.as_deref().map_or(Bound::Unbounded, |key| {
+                Bound::Included(M::Key::from_bytes(key))
+            }),
+            to.as_deref().map_or(Bound::Unbounded, |key| {
+                Bound::Excluded(M::Key::from_bytes(key))
+            }),
+        );
+
+        table
+            .range_models::<M, _>(range)?
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|model| {
+                let record = model?.to_record();
+                let cells = <M as Model>::SCHEMA
+                    .fields
+                    .iter()
+                    .zip(record)
+                    .map(|(field, value)| Cell::from_field(field.redb_type, value))
+                    .collect();
+                Ok(cells)
+            })
+            .collect()
+    }
+}
+
+/// Write the rows as a table, with columns aligned to the widest cell.
+fn write_table(out: &mut impl Write, header: &[String], rows: &[Vec<Cell>]) -> std::io::Result<()> {
+    let rows = rows
+        .iter()
+        .map(|row| row.iter().map(Cell::to_string).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let mut widths = header
+        .iter()
+        .map(|cell| cell.chars().count())
+        .collect::<Vec<_>>();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let rule = widths
+        .iter()
+        .map(|width| "-".repeat(*width))
+        .collect::<Vec<_>>();
+    for row in [header, &rule]
+        .into_iter()
+        .chain(rows.iter().map(Vec::as_slice))
+    {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(out, "{}", line.trim_end())?;
+    }
+
+    Ok(())
+}
+
+/// Write the rows as a JSON array of objects, keyed by the header.
+fn write_json(out: &mut impl Write, header: &[String], rows: &[Vec<Cell>]) -> std::io::Result<()> {
+    let objects = rows
+        .iter()
+        .map(|row| {
+            let fields = header
+                .iter()
+                .zip(row)
+                .map(|(name, cell)| format!("{}: {}", json_string(name), json_value(cell)))
+                .collect::<Vec<_>>();
+            format!("  {{{}}}", fields.join(", "))
+        })
+        .collect::<Vec<_>>();
+
+    match objects.is_empty() {
+        true => writeln!(out, "[]"),
+        false => writeln!(out, "[\n{}\n]", objects.join(",\n")),
+    }
+}
+
+/// Get the JSON value of a cell, where units and `None` are `null`, tuples and
+/// lists are arrays, and other values that are not numbers are strings.
+fn json_value(cell: &Cell) -> String {
+    match cell {
+        Cell::Unit | Cell::Option(None) => String::from("null"),
+        Cell::Bool(_) | Cell::Int(_) | Cell::UInt(_) => cell.to_string(),
+        Cell::Float(x) if x.is_finite() => cell.to_string(),
+        Cell::Option(Some(cell)) => json_value(cell),
+        Cell::Tuple(cells) | Cell::List(cells) => {
+            let values = cells.iter().map(json_value).collect::<Vec<_>>();
+            format!("[{}]", values.join(", "))
+        }
+        _ => json_string(&cell.to_string()),
+    }
+}
+
+/// Quote and escape a JSON string.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
@@ -0,0 +1,944 @@
+//! Rows decoded by the `redb` types stored within a table, without the model
+//! types.
+//!
+//! A table is opened with key and value types of the names and widths stored within
+//! the table definition, reading the raw bytes of each entry, which are decoded by
+//! the encoding of the `redb` types. Primitive types, `&str`, `String`, `&[u8]`,
+//! `()`, `Option`, arrays, `Vec` and tuples of these types are decoded, while values
+//! of other types are printed as hexadecimal. A tuple of several other types of a
+//! fixed width is printed as a single hexadecimal value.
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::error;
+use std::fmt;
+use std::ops::Bound;
+
+use redb::{Key, ReadTransaction, TableDefinition, TableError, TypeName, Value};
+
+use super::Inspect;
+use crate::schema::TableSchema;
+
+/// The maximum arity of a `redb` tuple. The fields of larger models are nested.
+const MAX_TUPLE_ARITY: usize = 12;
+
+thread_local! {
+    /// The key and value types of the table being read.
+    static TYPES: RefCell<Option<(Stored, Stored)>> = const { RefCell::new(None) };
+}
+
+/// A `redb` type, as named by `redb` or declared within a registered schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RedbType {
+    Unit,
+    Bool,
+    Char,
+    Int {
+        name: &'static str,
+        signed: bool,
+    },
+    F32,
+    F64,
+    Str,
+    String,
+    Bytes,
+    Option(Box<RedbType>),
+    Array(Box<RedbType>, usize),
+    Vec(Box<RedbType>),
+    Tuple(Vec<RedbType>),
+    /// A type that is not decoded, and the width, if known.
+    Other {
+        name: String,
+        width: Option<usize>,
+    },
+}
+
+/// The integer types, and whether each is signed.
+const INTS: [(&str, bool); 10] = [
+    ("u8", false),
+    ("u16", false),
+    ("u32", false),
+    ("u64", false),
+    ("u128", false),
+    ("i8", true),
+    ("i16", true),
+    ("i32", true),
+    ("i64", true),
+    ("i128", true),
+];
+
+impl RedbType {
+    /// Parse a type as named by `redb`, such as `(u32,&str)`, or as written within
+    /// a schema, such as `(u32, &'static str)`.
+    fn parse(ty: &str) -> Self {
+        let ty = ty.trim();
+        let other = || RedbType::Other {
+            name: ty.to_owned(),
+            width: None,
+        };
+
+        if let Some(elems) = ty.strip_prefix('(').and_then(|ty| ty.strip_suffix(')')) {
+            let Some(elems) = split(elems, ',') else {
+                return other();
+            };
+            let mut elems = elems
+                .into_iter()
+                .filter(|elem| !elem.trim().is_empty())
+                .map(Self::parse)
+                .collect::<Vec<_>>();
+            return match elems.len() {
+                0 => RedbType::Unit,
+                1 => elems.remove(0),
+                _ => RedbType::Tuple(elems),
+            };
+        }
+        if let Some(array) = ty.strip_prefix('[').and_then(|ty| ty.strip_suffix(']')) {
+            return match split(array, ';').as_deref() {
+                Some([elem]) if elem.trim() == "u8" => RedbType::Bytes,
+                Some([elem, len]) => match (Self::parse(elem), len.trim().parse()) {
+                    (RedbType::Other { .. }, _) | (_, Err(_)) => other(),
+                    (elem, Ok(len)) => RedbType::Array(Box::new(elem), len),
+                },
+                _ => other(),
+            };
+        }
+        if let Some(rest) = ty.strip_prefix('&') {
+            let rest = rest.trim_start();
+            let rest = match rest.strip_prefix('\'') {
+                Some(lifetime) => {
+                    lifetime.trim_start_matches(|c: char| c.is_alphanumeric() || c == '_')
+                }
+                None => rest,
+            };
+            return match (rest.trim(), Self::parse(rest)) {
+                ("str", _) => RedbType::Str,
+                (_, ty @ (RedbType::Bytes | RedbType::Array(..))) => ty,
+                _ => other(),
+            };
+        }
+
+        let (path, arg) = match ty.split_once('<') {
+            Some((path, arg)) => match arg.strip_suffix('>') {
+                Some(arg) => (path, Some(Self::parse(arg))),
+                None => return other(),
+            },
+            None => (ty, None),
+        };
+        let ident = path.trim().rsplit("::").next().unwrap_or_default();
+        match (ident, arg) {
+            ("bool", None) => RedbType::Bool,
+            ("char", None) => RedbType::Char,
+            ("f32", None) => RedbType::F32,
+            ("f64", None) => RedbType::F64,
+            ("String", None) => RedbType::String,
+            ("Option", Some(ty)) => RedbType::Option(Box::new(ty)),
+            // The encoding of the elements depends on whether their width is fixed.
+            ("Vec", Some(RedbType::Other { .. })) => other(),
+            ("Vec", Some(ty)) => RedbType::Vec(Box::new(ty)),
+            (ident, None) => INTS
+                .iter()
+                .find(|(name, _)| *name == ident)
+                .map_or_else(other, |&(name, signed)| RedbType::Int { name, signed }),
+            _ => other(),
+        }
+    }
+
+    /// The name of the type within `redb`.
+    fn name(&self) -> String {
+        match self {
+            RedbType::Unit => String::from("()"),
+            RedbType::Bool => String::from("bool"),
+            RedbType::Char => String::from("char"),
+            RedbType::Int { name, .. } => name.to_string(),
+            RedbType::F32 => String::from("f32"),
+            RedbType::F64 => String::from("f64"),
+            RedbType::Str => String::from("&str"),
+            RedbType::String => String::from("String"),
+            RedbType::Bytes => String::from("&[u8]"),
+            RedbType::Option(ty) => format!("Option<{}>", ty.name()),
+            RedbType::Array(ty, len) => format!("[{};{}]", ty.name(), len),
+            RedbType::Vec(ty) => format!("Vec<{}>", ty.name()),
+            RedbType::Tuple(elems) => {
+                let names = elems.iter().map(RedbType::name).collect::<Vec<_>>();
+                format!("({})", names.join(","))
+            }
+            RedbType::Other { name, .. } => name.clone(),
+        }
+    }
+
+    /// The width of the encoded type, if fixed.
+    fn fixed_width(&self) -> Option<usize> {
+        match self {
+            RedbType::Unit => Some(0),
+            RedbType::Bool => Some(1),
+            RedbType::Char => Some(3),
+            RedbType::Int { name, .. } => Some(name[1..].parse::<usize>().ok()? / 8),
+            RedbType::F32 => Some(4),
+            RedbType::F64 => Some(8),
+            RedbType::Str | RedbType::String | RedbType::Bytes | RedbType::Vec(_) => None,
+            RedbType::Option(ty) => Some(ty.fixed_width()? + 1),
+            RedbType::Array(ty, len) => Some(ty.fixed_width()? * len),
+            RedbType::Tuple(elems) => elems.iter().map(RedbType::fixed_width).sum(),
+            RedbType::Other { width, .. } => *width,
+        }
+    }
+
+    /// Resolve the width of other types from the width of the type, as stored
+    /// within the table definition. A tuple of several other types of a fixed width
+    /// is resolved as a single other type.
+    fn resolve(&mut self, width: Option<usize>) {
+        let Some(width) = width else {
+            return;
+        };
+        match self {
+            RedbType::Other { width: other, .. } => *other = Some(width),
+            RedbType::Option(ty) => ty.resolve(width.checked_sub(1)),
+            RedbType::Tuple(elems) => {
+                let known = elems
+                    .iter()
+                    .filter_map(RedbType::fixed_width)
+                    .sum::<usize>();
+                match elems.iter().filter(|ty| ty.fixed_width().is_none()).count() {
+                    0 => {}
+                    1 => {
+                        if let Some(ty) = elems.iter_mut().find(|ty| ty.fixed_width().is_none()) {
+                            ty.resolve(width.checked_sub(known));
+                        }
+                    }
+                    _ => {
+                        *self = RedbType::Other {
+                            name: self.name(),
+                            width: Some(width),
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the types are equal, where other types are equal to any type.
+    fn matches(&self, other: &RedbType) -> bool {
+        match (self, other) {
+            (RedbType::Other { .. }, _) | (_, RedbType::Other { .. }) => true,
+            (RedbType::Option(a), RedbType::Option(b)) | (RedbType::Vec(a), RedbType::Vec(b)) => {
+                a.matches(b)
+            }
+            (RedbType::Array(a, n), RedbType::Array(b, m)) => n == m && a.matches(b),
+            (RedbType::Tuple(a), RedbType::Tuple(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.matches(b))
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// Decode a value of the type. Returns an error if the value is not of the type.
+    fn decode(&self, data: &[u8]) -> Result<Cell, String> {
+        let invalid = || format!("invalid `{}` of {} bytes", self.name(), data.len());
+        match self {
+            RedbType::Unit => Ok(Cell::Unit),
+            RedbType::Bool => match data {
+                [b] => Ok(Cell::Bool(*b != 0)),
+                _ => Err(invalid()),
+            },
+            RedbType::Char => match data {
+                [b0, b1, b2] => char::from_u32(u32::from_le_bytes([*b0, *b1, *b2, 0]))
+                    .map(Cell::Char)
+                    .ok_or_else(invalid),
+                _ => Err(invalid()),
+            },
+            RedbType::Int { .. } if Some(data.len()) != self.fixed_width() => Err(invalid()),
+            RedbType::Int { signed: true, .. } => {
+                // Sign extend the little endian bytes.
+                let fill = if data.last().is_some_and(|b| b & 0x80 != 0) {
+                    0xff
+                } else {
+                    0
+                };
+                let mut bytes = [fill; 16];
+                bytes[..data.len()].copy_from_slice(data);
+                Ok(Cell::Int(i128::from_le_bytes(bytes)))
+            }
+            RedbType::Int { signed: false, .. } => {
+                let mut bytes = [0; 16];
+                bytes[..data.len()].copy_from_slice(data);
+                Ok(Cell::UInt(u128::from_le_bytes(bytes)))
+            }
+            RedbType::F32 => data
+                .try_into()
+                .map(|bytes| Cell::Float(f32::from_le_bytes(bytes) as f64))
+                .map_err(|_| invalid()),
+            RedbType::F64 => data
+                .try_into()
+                .map(|bytes| Cell::Float(f64::from_le_bytes(bytes)))
+                .map_err(|_| invalid()),
+            RedbType::Str | RedbType::String => {
+                Ok(Cell::Str(String::from_utf8_lossy(data).into_owned()))
+            }
+            RedbType::Bytes | RedbType::Other { .. } => Ok(Cell::Bytes(data.to_vec())),
+            RedbType::Option(ty) => match data.split_first() {
+                Some((0, _)) => Ok(Cell::Option(None)),
+                Some((1, data)) => Ok(Cell::Option(Some(Box::new(ty.decode(data)?)))),
+                _ => Err(invalid()),
+            },
+            RedbType::Array(ty, len) => {
+                let slices = array_slices(ty, *len, data).ok_or_else(invalid)?;
+                list(ty, slices)
+            }
+            RedbType::Vec(ty) => {
+                let slices = vec_slices(ty, data).ok_or_else(invalid)?;
+                list(ty, slices)
+            }
+            RedbType::Tuple(elems) => tuple_slices(elems, data)
+                .ok_or_else(invalid)?
+                .into_iter()
+                .zip(elems)
+                .map(|(data, ty)| ty.decode(data))
+                .collect::<Result<_, _>>()
+                .map(Cell::Tuple),
+        }
+    }
+
+    /// Encode a value of the type.
+    fn encode(&self, cell: &Cell) -> Vec<u8> {
+        match (self, cell) {
+            (RedbType::Bool, Cell::Bool(b)) => vec![*b as u8],
+            (RedbType::Char, Cell::Char(c)) => u32::from(*c).to_le_bytes()[..3].to_vec(),
+            (RedbType::Int { .. }, Cell::Int(i)) => {
+                i.to_le_bytes()[..self.fixed_width().unwrap_or(0)].to_vec()
+            }
+            (RedbType::Int { .. }, Cell::UInt(u)) => {
+                u.to_le_bytes()[..self.fixed_width().unwrap_or(0)].to_vec()
+            }
+            (RedbType::F32, Cell::Float(f)) => (*f as f32).to_le_bytes().to_vec(),
+            (RedbType::F64, Cell::Float(f)) => f.to_le_bytes().to_vec(),
+            (RedbType::Str | RedbType::String, Cell::Str(s)) => s.as_bytes().to_vec(),
+            (RedbType::Bytes | RedbType::Array(..), Cell::Bytes(b)) => b.clone(),
+            (RedbType::Option(ty), Cell::Option(cell)) => {
+                let mut data = vec![cell.is_some() as u8];
+                match (cell, ty.fixed_width()) {
+                    (Some(cell), _) => data.extend(ty.encode(cell)),
+                    (None, Some(width)) => data.resize(width + 1, 0),
+                    (None, None) => {}
+                }
+                data
+            }
+            (RedbType::Tuple(elems), Cell::Tuple(cells)) => {
+                let data = elems
+                    .iter()
+                    .zip(cells)
+                    .map(|(ty, cell)| ty.encode(cell))
+                    .collect::<Vec<_>>();
+                let mut encoded = Vec::new();
+                if self.fixed_width().is_none() {
+                    for data in &data[..data.len() - 1] {
+                        encoded.extend((data.len() as u32).to_le_bytes());
+                    }
+                }
+                encoded.extend(data.into_iter().flatten());
+                encoded
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Parse a value of the type from the CSV form of a field.
+    fn parse_cell(&self, field: &str) -> Result<Cell, String> {
+        let invalid =
+            |e: &dyn fmt::Display| format!("invalid `{}` key `{}`: {}", self.name(), field, e);
+        match self {
+            RedbType::Unit if field.is_empty() => Ok(Cell::Unit),
+            RedbType::Bool => field.parse().map(Cell::Bool).map_err(|e| invalid(&e)),
+            RedbType::Char => field.parse().map(Cell::Char).map_err(|e| invalid(&e)),
+            RedbType::Int { signed, .. } => {
+                let bits = self.fixed_width().unwrap_or(16) * 8;
+                match signed {
+                    true => field
+                        .parse::<i128>()
+                        .map_err(|e| invalid(&e))
+                        .and_then(|i| match i.checked_shr(bits as u32 - 1) {
+                            Some(0 | -1) | None => Ok(Cell::Int(i)),
+                            Some(_) => Err(invalid(&"out of range")),
+                        }),
+                    false => field
+                        .parse::<u128>()
+                        .map_err(|e| invalid(&e))
+                        .and_then(|u| match u.checked_shr(bits as u32) {
+                            Some(0) | None => Ok(Cell::UInt(u)),
+                            Some(_) => Err(invalid(&"out of range")),
+                        }),
+                }
+            }
+            RedbType::F32 | RedbType::F64 => {
+                field.parse().map(Cell::Float).map_err(|e| invalid(&e))
+            }
+            RedbType::Str | RedbType::String => Ok(Cell::Str(field.to_owned())),
+            RedbType::Bytes => parse_hex(field)
+                .map(Cell::Bytes)
+                .ok_or_else(|| invalid(&"not hexadecimal")),
+            RedbType::Array(ty, len)
+                if **ty
+                    == RedbType::Int {
+                        name: "u8",
+                        signed: false,
+                    } =>
+            {
+                match parse_hex(field) {
+                    Some(bytes) if bytes.len() == *len => Ok(Cell::Bytes(bytes)),
+                    _ => Err(invalid(&format_args!("not {} hexadecimal bytes", len))),
+                }
+            }
+            RedbType::Option(_) if field.is_empty() => Ok(Cell::Option(None)),
+            RedbType::Option(ty) => Ok(Cell::Option(Some(Box::new(ty.parse_cell(field)?)))),
+            _ => Err(format!("unsupported key type `{}`", self.name())),
+        }
+    }
+}
+
+/// Split the string at each separator outside of brackets. Returns `None` if the
+/// brackets are not balanced.
+fn split(s: &str, separator: char) -> Option<Vec<&str>> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' | '[' | '<' => depth += 1,
+            ')' | ']' | '>' => depth = depth.checked_sub(1)?,
+            c if c == separator && depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+
+    (depth == 0).then_some(parts)
+}
+
+/// A `redb` value decoded by a `RedbType`. Values of the same type are ordered
+/// as within a `redb` table.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub(super) enum Cell {
+    Unit,
+    Bool(bool),
+    Char(char),
+    Int(i128),
+    UInt(u128),
+    Float(f64),
+    Str(String),
+    Bytes(Vec<u8>),
+    Option(Option<Box<Cell>>),
+    Tuple(Vec<Cell>),
+    List(Vec<Cell>),
+}
+
+impl Cell {
+    /// Get the cell of a field formatted by `ToString`, parsed as the `redb` type of
+    /// the field, or as a string if the field is not formatted as the type.
+    pub(super) fn from_field(redb_type: &str, field: String) -> Self {
+        match RedbType::parse(redb_type).parse_cell(&field) {
+            Ok(cell) if cell.to_string() == field => cell,
+            _ => Cell::Str(field),
+        }
+    }
+}
+
+/// Formats the value as a field of a row, printing bytes as hexadecimal, and
+/// `None` as an empty field.
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cell::Unit | Cell::Option(None) => Ok(()),
+            Cell::Bool(b) => b.fmt(f),
+            Cell::Char(c) => c.fmt(f),
+            Cell::Int(i) => i.fmt(f),
+            Cell::UInt(u) => u.fmt(f),
+            Cell::Float(x) => x.fmt(f),
+            Cell::Str(s) => s.fmt(f),
+            Cell::Bytes(bytes) => bytes.iter().try_for_each(|b| write!(f, "{:02x}", b)),
+            Cell::Option(Some(cell)) => cell.fmt(f),
+            Cell::Tuple(cells) => {
+                let cells = cells.iter().map(Cell::to_string).collect::<Vec<_>>();
+                write!(f, "({})", cells.join(", "))
+            }
+            Cell::List(cells) => {
+                let cells = cells.iter().map(Cell::to_string).collect::<Vec<_>>();
+                write!(f, "[{}]", cells.join(", "))
+            }
+        }
+    }
+}
+
+/// A `redb` type, and the name and width stored within a table definition.
+#[derive(Debug, Clone)]
+struct Stored {
+    name: TypeName,
+    width: Option<usize>,
+    ty: RedbType,
+}
+
+impl Stored {
+    fn new(name: TypeName, text: &str) -> Self {
+        let ty = RedbType::parse(text);
+        Self {
+            width: ty.fixed_width(),
+            name,
+            ty,
+        }
+    }
+}
+
+/// Get the key and value types stored within the definition of the table, from
+/// the errors of opening the table with other types.
+fn stored_types(txn: &ReadTransaction, table: &str) -> Result<(Stored, Stored), TableError> {
+    let mut key = Stored::new(TypeName::new(""), "");
+    let mut value = key.clone();
+
+    // The names are found, followed by the width of the key, then the value.
+    for _ in 0..4 {
+        let _types = TypesGuard::set(key.clone(), value.clone());
+        match txn.open_table(TableDefinition::<Raw<true>, Raw<false>>::new(table)) {
+            Ok(_) => break,
+            Err(e @ TableError::TableTypeMismatch { .. }) => {
+                // The names are only public within the message of the error.
+                let message = e.to_string();
+                let names = message
+                    .strip_prefix(&format!("{} is of type Table<", table))
+                    .and_then(|names| names.strip_suffix('>'))
+                    .and_then(|names| split(names, ','));
+                let (
+                    TableError::TableTypeMismatch {
+                        key: k, value: v, ..
+                    },
+                    Some([k_name, v_name]),
+                ) = (e, names.as_deref())
+                else {
+                    return Err(TableError::TableTypeMismatch {
+                        table: table.to_owned(),
+                        key: key.name,
+                        value: value.name,
+                    });
+                };
+                key = Stored::new(k, k_name.trim());
+                value = Stored::new(v, v_name.trim());
+            }
+            Err(TableError::TypeDefinitionChanged { name, width, .. }) => {
+                for stored in [&mut key, &mut value] {
+                    if stored.name == name {
+                        stored.width = width;
+                    }
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    key.ty.resolve(key.width);
+    value.ty.resolve(value.width);
+    Ok((key, value))
+}
+
+/// The layout of the rows of a table, by the registered schema and the stored
+/// types.
+pub(super) struct Layout {
+    schema: TableSchema,
+    key: Stored,
+    value: Stored,
+    key_fields: Vec<RedbType>,
+    value_fields: usize,
+    tombstone: bool,
+}
+
+impl Layout {
+    /// Get the layout of the table of the registered schema.
+    pub(super) fn open(
+        txn: &ReadTransaction,
+        schema: TableSchema,
+    ) -> Result<Self, Box<dyn error::Error>> {
+        if schema.table_type != "table" {
+            return Err(format!("unsupported table type `{}`", schema.table_type).into());
+        }
+        let (key, value) = stored_types(txn, &schema.name)?;
+
+        let key_fields = unnest(
+            key.ty.clone(),
+            schema.fields.iter().filter(|field| field.key).count(),
+        );
+        let mut value_fields = schema
+            .fields
+            .iter()
+            .filter(|field| !field.key)
+            .map(|field| RedbType::parse(&field.redb_type))
+            .collect::<Vec<_>>();
+
+        // The value of a `soft_delete` model appends a tombstone.
+        let len = value_fields.len();
+        let tombstone = !nest(value_fields.clone()).matches(&value.ty) && {
+            value_fields.push(RedbType::Bool);
+            nest(value_fields).matches(&value.ty)
+        };
+
+        Ok(Self {
+            schema,
+            key,
+            value,
+            key_fields,
+            value_fields: len,
+            tombstone,
+        })
+    }
+}
+
+impl Inspect for Layout {
+    fn schema(&self) -> TableSchema {
+        self.schema.clone()
+    }
+
+    fn key_bytes(&self, record: &[&str]) -> Result<Vec<u8>, String> {
+        if record.len() != self.key_fields.len() {
+            return Err(format!(
+                "expected {} key fields, found {}",
+                self.key_fields.len(),
+                record.len()
+            ));
+        }
+        let cells = self
+            .key_fields
+            .iter()
+            .zip(record)
+            .map(|(ty, field)| ty.parse_cell(field))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(self.key.ty.encode(&nest(cells)))
+    }
+
+    /// Skips soft removed rows.
+    fn rows(
+        &self,
+        txn: &ReadTransaction,
+        from: Option<Vec<u8>>,
+        to: Option<Vec<u8>>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Vec<Cell>>, Box<dyn error::Error>> {
+        let _types = TypesGuard::set(self.key.clone(), self.value.clone());
+        let table = txn.open_table(TableDefinition::<Raw<true>, Raw<false>>::new(
+            &self.schema.name,
+        ))?;
+        let range = (
+            from.as_deref().map_or(Bound::Unbounded, Bound::Included),
+            to.as_deref().map_or(Bound::Unbounded, Bound::Excluded),
+        );
+        let invalid = |e: String| format!("invalid row of `{}`: {}", self.schema.name, e);
+
+        let mut rows = Vec::new();
+        for entry in table.range::<&[u8]>(range)? {
+            if rows.len() >= limit.unwrap_or(usize::MAX) {
+                break;
+            }
+            let (k, v) = entry?;
+            let key = self.key.ty.decode(k.value()).map_err(invalid)?;
+            let value = self.value.ty.decode(v.value()).map_err(invalid)?;
+
+            let key = unnest(key, self.key_fields.len());
+            let mut value = unnest(value, self.value_fields + self.tombstone as usize);
+            if self.tombstone && value.pop() == Some(Cell::Bool(true)) {
+                continue;
+            }
+            rows.push(key.into_iter().chain(value).collect());
+        }
+
+        Ok(rows)
+    }
+}
+
+/// A type or value of a key or value, combining the fields of a model.
+trait Nested: Sized {
+    /// The combination of no elements.
+    fn unit() -> Self;
+
+    /// The combination of the elements as a tuple.
+    fn tuple(elems: Vec<Self>) -> Self;
+
+    /// Get the elements of a tuple, or the element if not a tuple.
+    fn elems(self) -> Result<Vec<Self>, Self>;
+}
+
+impl Nested for RedbType {
+    fn unit() -> Self {
+        RedbType::Unit
+    }
+
+    fn tuple(elems: Vec<Self>) -> Self {
+        RedbType::Tuple(elems)
+    }
+
+    fn elems(self) -> Result<Vec<Self>, Self> {
+        match self {
+            RedbType::Tuple(elems) => Ok(elems),
+            ty => Err(ty),
+        }
+    }
+}
+
+impl Nested for Cell {
+    fn unit() -> Self {
+        Cell::Unit
+    }
+
+    fn tuple(elems: Vec<Self>) -> Self {
+        Cell::Tuple(elems)
+    }
+
+    fn elems(self) -> Result<Vec<Self>, Self> {
+        match self {
+            Cell::Tuple(cells) => Ok(cells),
+            cell => Err(cell),
+        }
+    }
+}
+
+/// Combine elements into a single element, or a tuple of elements, as declared
+/// by the `Model` derive. Tuples exceeding `MAX_TUPLE_ARITY` are nested.
+fn nest<T: Nested>(mut elems: Vec<T>) -> T {
+    match elems.len() {
+        0 => T::unit(),
+        1 => elems.remove(0),
+        len if len <= MAX_TUPLE_ARITY => T::tuple(elems),
+        _ => {
+            let mut chunks = Vec::new();
+            let mut elems = elems.into_iter().peekable();
+            while elems.peek().is_some() {
+                chunks.push(nest(elems.by_ref().take(MAX_TUPLE_ARITY).collect()));
+            }
+            nest(chunks)
+        }
+    }
+}
+
+/// Split an element nested by `nest` into the given number of elements. An element
+/// that is not a tuple of the number of elements, such as a tuple of other types
+/// printed as hexadecimal, is followed by units.
+fn unnest<T: Nested>(elem: T, len: usize) -> Vec<T> {
+    match len {
+        0 => Vec::new(),
+        1 => vec![elem],
+        len if len <= MAX_TUPLE_ARITY => {
+            let mut elems = elem.elems().unwrap_or_else(|elem| vec![elem]);
+            elems.resize_with(len, T::unit);
+            elems
+        }
+        len => {
+            let chunks = len.div_ceil(MAX_TUPLE_ARITY);
+            unnest(elem, chunks)
+                .into_iter()
+                .enumerate()
+                .flat_map(|(i, chunk)| {
+                    let chunk_len = MAX_TUPLE_ARITY.min(len - i * MAX_TUPLE_ARITY);
+                    unnest(chunk, chunk_len)
+                })
+                .collect()
+        }
+    }
+}
+
+/// Decode the elements of an array or `Vec`, as bytes if the elements are `u8`.
+fn list(ty: &RedbType, slices: Vec<&[u8]>) -> Result<Cell, String> {
+    let cells = slices
+        .into_iter()
+        .map(|data| ty.decode(data))
+        .collect::<Result<Vec<_>, _>>()?;
+    let bytes = cells
+        .iter()
+        .map(|cell| match (ty, cell) {
+            (RedbType::Int { name: "u8", .. }, Cell::UInt(u)) => Some(*u as u8),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>();
+
+    Ok(match bytes {
+        Some(bytes) => Cell::Bytes(bytes),
+        None => Cell::List(cells),
+    })
+}
+
+/// Read a little endian `u32` at the offset.
+fn read_u32(data: &[u8], offset: usize) -> Option<usize> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?) as usize)
+}
+
+/// Read a length encoded by `redb` as a variable width integer at the offset,
+/// advancing past it.
+fn read_varint(data: &[u8], offset: &mut usize) -> Option<usize> {
+    let (len, width) = match *data.get(*offset)? {
+        254 => {
+            let bytes = data.get(*offset + 1..*offset + 3)?;
+            (u16::from_le_bytes(bytes.try_into().ok()?) as usize, 3)
+        }
+        255 => (read_u32(data, *offset + 1)?, 5),
+        len => (len as usize, 1),
+    };
+    *offset += width;
+
+    Some(len)
+}
+
+/// Split an encoded tuple into the encoded elements.
+fn tuple_slices<'a>(elems: &[RedbType], data: &'a [u8]) -> Option<Vec<&'a [u8]>> {
+    let widths = elems
+        .iter()
+        .map(RedbType::fixed_width)
+        .collect::<Option<Vec<_>>>();
+    let mut slices = Vec::with_capacity(elems.len());
+    let mut offset = 0usize;
+    match widths {
+        Some(widths) => {
+            for width in widths {
+                slices.push(data.get(offset..offset.checked_add(width)?)?);
+                offset += width;
+            }
+            (offset == data.len()).then_some(slices)
+        }
+        None => {
+            // Each element but the last is prefixed by its length.
+            let prefixed = elems.len().checked_sub(1)?;
+            offset = prefixed * 4;
+            for i in 0..prefixed {
+                let len = read_u32(data, i * 4)?;
+                slices.push(data.get(offset..offset.checked_add(len)?)?);
+                offset += len;
+            }
+            slices.push(data.get(offset..)?);
+            Some(slices)
+        }
+    }
+}
+
+/// Split an encoded array into the encoded elements.
+fn array_slices<'a>(ty: &RedbType, len: usize, data: &'a [u8]) -> Option<Vec<&'a [u8]>> {
+    match ty.fixed_width() {
+        Some(width) if width.checked_mul(len)? == data.len() => Some(
+            (0..len)
+                .map(|i| &data[i * width..(i + 1) * width])
+                .collect(),
+        ),
+        Some(_) => None,
+        None => {
+            // Each element is preceded by the offset of its end.
+            let mut start = len.checked_mul(4)?;
+            (0..len)
+                .map(|i| {
+                    let end = read_u32(data, i * 4)?;
+                    let slice = data.get(start..end)?;
+                    start = end;
+                    Some(slice)
+                })
+                .collect()
+        }
+    }
+}
+
+/// Split an encoded `Vec` into the encoded elements.
+fn vec_slices<'a>(ty: &RedbType, data: &'a [u8]) -> Option<Vec<&'a [u8]>> {
+    let mut offset = 0usize;
+    let len = read_varint(data, &mut offset)?;
+    let mut slices = Vec::new();
+    for _ in 0..len {
+        let width = match ty.fixed_width() {
+            Some(width) => width,
+            None => read_varint(data, &mut offset)?,
+        };
+        slices.push(data.get(offset..offset.checked_add(width)?)?);
+        offset += width;
+    }
+
+    (offset == data.len()).then_some(slices)
+}
+
+/// Parse hexadecimal bytes.
+fn parse_hex(s: &str) -> Option<Vec<u8>> {
+    s.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [high, low] => Some((hex_digit(*high)? << 4) | hex_digit(*low)?),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Parse a hexadecimal digit.
+fn hex_digit(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|digit| digit as u8)
+}
+
+/// Sets the key and value types of the table being read, until dropped.
+struct TypesGuard;
+
+impl TypesGuard {
+    fn set(key: Stored, value: Stored) -> Self {
+        TYPES.with(|types| *types.borrow_mut() = Some((key, value)));
+        TypesGuard
+    }
+}
+
+impl Drop for TypesGuard {
+    fn drop(&mut self) {
+        TYPES.with(|types| *types.borrow_mut() = None);
+    }
+}
+
+/// The raw bytes of the key (`KEY`), or value of the table being read, of the
+/// type set by a `TypesGuard`.
+#[derive(Debug)]
+struct Raw<const KEY: bool>;
+
+impl<const KEY: bool> Raw<KEY> {
+    /// Call the function with the type of the table being read.
+    fn with<T>(f: impl FnOnce(&Stored) -> T) -> T {
+        TYPES.with(|types| {
+            let types = types.borrow();
+            let (key, value) = types.as_ref().expect("types of the table being read");
+            f(if KEY { key } else { value })
+        })
+    }
+}
+
+impl<const KEY: bool> Value for Raw<KEY> {
+    type SelfType<'a> = &'a [u8];
+    type AsBytes<'a> = &'a [u8];
+
+    fn fixed_width() -> Option<usize> {
+        Self::with(|stored| stored.width)
+    }
+
+    fn from_bytes<'a>(data: &'a [u8]) -> &'a [u8]
+    where
+        Self: 'a,
+    {
+        data
+    }
+
+    fn as_bytes<'a, 'b: 'a>(value: &'a &'b [u8]) -> &'a [u8]
+    where
+        Self: 'a,
+        Self: 'b,
+    {
+        value
+    }
+
+    fn type_name() -> TypeName {
+        Self::with(|stored| stored.name.clone())
+    }
+}
+
+impl<const KEY: bool> Key for Raw<KEY> {
+    /// Compares the decoded values, or the bytes of values that are not decoded.
+    fn compare(data1: &[u8], data2: &[u8]) -> Ordering {
+        Self::with(
+            |stored| match (stored.ty.decode(data1), stored.ty.decode(data2)) {
+                (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+                _ => data1.cmp(data2),
+            },
+        )
+    }
+}
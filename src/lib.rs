@@ -94,8 +94,17 @@
 //! `table_type` | Table type, either `table` or `multimap` | `Literal` | `table`
 //! `impl_ext` | Implement [`ModelExt`] for the type | `bool` | `false`
 //! `impl_from` | Implement `From<T>`, mapping `T` to `ModelExt::from_values(T)` and `ModelExt::from_guards(T)`. | `bool` | `false`
-//!
-//! Note that `impl_from` uses methods of `impl_ext` and therefore requires both
+//! `infer_borrowed` | Infer a borrowed `redb_type` for common owned field types (see below). | `bool` | `false`
+//! `validate` | A function validating the model (see below). | `Path` | `None`
+//! `hooks` | The model implements [`ModelHooks`], rather than the default no-op hooks. | `bool` | `false`
+//! `soft_delete` | Append a removal mark to the table value, allowing models to be soft removed (see below). | `bool` | `false`
+//! `csv` | Implement [`CsvModel`] for the type, converting fields with `ToString` and `FromStr` (see below). | `bool` | `false`
+//! `patch` | Declare a `<Name>Patch` struct of optional value fields, and implement [`ModelPatch`] for the type (see below). | `bool` | `false`
+//! `patch_derive` | Traits derived by the patch struct, in addition to `Default`. | `List` | `()`
+//! `diff` | Implement [`ModelDiff`] for the type, comparing fields with `PartialEq` and formatting them with `Debug` (see below). | `bool` | `false`
+//! `history` | Record the previous models of changes made with a [`WriteTxn`] in a `<name>_history` table (see below). | `bool` | `false`
+//!
+//! Note that `impl_from`, `csv` and `history` use methods of `impl_ext` and therefore require both
 //! arguments to be specified.
 //!
 //! ## Field Attributes
@@ -105,7 +114,13 @@
 //! When generating a `ModelExt` definition (providing `impl_ext` as a struct argument),
 //! `from` and `into` operations may need to be explicit. Note that composite
 //! variables (multiple `key` or `value` fields) are combined as tuples in the order
-//! they are defined.
+//! they are defined. As `redb` implements `Key` and `Value` for tuples of at most 12
+//! elements, larger composites are nested as tuples of 12 elements, for example
+//! `((T0, .., T11), (T12, T13))`.
+//!
+//! Reordering fields changes the table layout. To decouple the layout from the
+//! declaration order, `order` may be declared on every `key` (or every `value`)
+//! field, numbering the fields from `0` without gaps or duplicates.
 //!
 //! Argument | Description | Type | Default
 //! ---|---|---|---
@@ -113,6 +128,12 @@
 //! `redb_type` | The type defined in the `redb::TableDefinition` or `redb::MultimapTableDefinition`. | `Type` | Field `Type`
 //! `from` | The operation to convert **from** the `redb_type`.  | `Expression` | See below.
 //! `into` | The operation to convert **into** the `redb_type`.  | `Expression` | See below.
+//! `order` | The index of the field within the key or value tuple. | `usize` | Declaration order
+//! `adapter` | An adapter declaring the `redb_type`, `from` and `into` operations. | `Path` | `None`
+//! `validate` | A function validating the field (see below). | `Path` | `None`
+//! `created_at` | Set the field to the current time when inserted (see below). | `bool` | `false`
+//! `updated_at` | Set the field to the current time when inserted or updated (see below). | `bool` | `false`
+//! `version` | The `u64` value field checked and incremented when updated (see below). | `bool` | `false`
 //!
 //! Conversion `from` a `redb` value has the following default behavior (`impl_ext` only):
 //! - If no `redb_type` is specified, the value is assumed to implement `Copy` and passed directly to the DTO.
@@ -124,6 +145,12 @@
 //! - If the specified `redb_type` is a reference (is prefixed by `&`), the value is passed as a reference.
 //! - If the specified `redb_type` is not a reference, `into` is called on the value.
 //!
+//! With `infer_borrowed`, fields declaring no `redb_type` are stored in their borrowed
+//! form, along with the matching `from` and `into` operations:
+//! - `String`, `Box<str>`, `Rc<str>`, `Arc<str>` and `Cow<str>` are declared as `&str`.
+//! - `Vec<u8>`, `Box<[u8]>`, `Rc<[u8]>`, `Arc<[u8]>` and `Cow<[u8]>` are declared as `&[u8]`.
+//! - `PathBuf` is declared as `&[u8]`, converted by the `std_path` adapter.
+//!
 //! For user defined types, typically implementing `From<RedbType> for FieldType` and
 //! `Into<RedbType> for &FieldType` will satisfy type conversion.
 //!
@@ -187,6 +214,712 @@
 //! }
 //! ```
 //!
+//! Common external types can instead declare an `adapter`, implementing the
+//! `redb_type`, `from` and `into` operations. Built-in adapters are declared by
+//! name, while any other path is assumed to implement `redb_model::adapter::Adapter`
+//! for the field type. See the [`adapter`] module for the built-in adapters and the
+//! cargo features enabling them.
+//!
+//! ```rust
+//! # use redb_model::{Model, ModelExt};
+//! use std::net::IpAddr;
+//! use std::time::SystemTime;
+//!
+//! #[derive(Model)]
+//! #[model(impl_ext)]
+//! struct AdaptedModel {
+//!     #[entry(position = "key", adapter = "net")]
+//!     address: IpAddr,
+//!     #[entry(position = "value", adapter = "std_time")]
+//!     created: SystemTime,
+//! }
+//! ```
+//!
+//! ## Validation
+//!
+//! Fields and models may declare a `validate` function, accepting a reference of
+//! the field or model and returning a `Result<(), E>` where `E: Display`. Key field
+//! functions run first, followed by value fields and the model function. Validation
+//! runs on `ModelExt::validate`, and the fallible `try_` variants of the
+//! conversion methods, returning a `ValidationError` naming the rejected field.
+//!
+//! ```rust
+//! # use redb_model::{Model, ModelExt};
+//! fn non_empty(value: &str) -> Result<(), &'static str> {
+//!     match value.is_empty() {
+//!         true => Err("must not be empty"),
+//!         false => Ok(()),
+//!     }
+//! }
+//!
+//! #[derive(Model)]
+//! #[model(impl_ext)]
+//! struct User {
+//!     #[entry(position = "key")]
+//!     id: u32,
+//!     #[entry(position = "value", redb_type = "&str", validate = "non_empty")]
+//!     name: String,
+//! }
+//!
+//! let user = User { id: 0, name: String::new() };
+//! let e = user.try_as_key_and_value().unwrap_err();
+//! assert_eq!(e.field_name(), Some("name"));
+//! ```
+//!
+//! ## Table Operations
+//!
+//! The [`ReadableModelTable`] and [`ModelTable`] traits extend `redb` tables with
+//! operations on models, decoding models with the [`ModelExt`] methods.
+//! `ModelTable::insert_model` validates the model before inserting it, and
+//! both insertion and removal return the replaced or removed model. The
+//! `replace_model` and `take_model` variants write the model as given, without
+//! hooks, validation or timestamps, such as to restore a previous model.
+//!
+//! ```rust
+//! # use redb::backends::InMemoryBackend;
+//! # use redb::Database;
+//! # use redb_model::{Model, ModelExt, ModelTable, ReadableModelTable};
+//! # let db = Database::builder()
+//! #     .create_with_backend(InMemoryBackend::new())
+//! #     .unwrap();
+//! #[derive(Model, Debug, PartialEq, Eq)]
+//! #[model(impl_ext)]
+//! struct User {
+//!     #[entry(position = "key")]
+//!     id: u32,
+//!     #[entry(position = "value", redb_type = "&str")]
+//!     name: String,
+//! }
+//!
+//! let mut user = User { id: 0, name: String::from("name") };
+//!
+//! let txn = db.begin_write().unwrap();
+//! {
+//!     let mut table = txn.open_table(User::DEFINITION).unwrap();
+//!     table.insert_model(&mut user).unwrap();
+//! }
+//! txn.commit().unwrap();
+//!
+//! let txn = db.begin_read().unwrap();
+//! let table = txn.open_table(User::DEFINITION).unwrap();
+//! assert_eq!(table.get_model::<User>(0).unwrap(), Some(user));
+//! ```
+//!
+//! ### Patches
+//!
+//! Models declaring `patch` are accompanied by a `<Name>Patch` struct, with an
+//! `Option` of each value field, and implement [`ModelPatch`]. The `version`,
+//! `created_at` and `updated_at` fields are set by writes, and are not patched.
+//! `ModelTable::patch_model` loads the model of a key, replaces each field that is
+//! `Some`, and replaces the stored model as with `update_model`, returning the
+//! patched model.
+//!
+//! ```rust
+//! # use redb::backends::InMemoryBackend;
+//! # use redb::Database;
+//! # use redb_model::{Model, ModelTable};
+//! # let db = Database::builder()
+//! #     .create_with_backend(InMemoryBackend::new())
+//! #     .unwrap();
+//! #[derive(Model, Debug, PartialEq, Eq)]
+//! #[model(impl_ext, patch, patch_derive(Debug))]
+//! struct User {
+//!     #[entry(position = "key")]
+//!     id: u32,
+//!     #[entry(position = "value", redb_type = "&str")]
+//!     name: String,
+//!     #[entry(position = "value")]
+//!     age: u8,
+//! }
+//!
+//! let txn = db.begin_write().unwrap();
+//! let mut table = txn.open_table(User::DEFINITION).unwrap();
+//! table.insert_model(&mut User { id: 0, name: String::from("name"), age: 20 }).unwrap();
+//!
+//! let patch = UserPatch { age: Some(21), ..Default::default() };
+//! let user = table.patch_model::<User>(0, patch).unwrap();
+//! assert_eq!(user, User { id: 0, name: String::from("name"), age: 21 });
+//! ```
+//!
+//! ### Diffs
+//!
+//! Models declaring `diff` implement [`ModelDiff`], listing each [`FieldChange`]
+//! between two models with the `Debug` formatted values before and after, such as
+//! for an audit trail of the `Change::Update` notifications.
+//!
+//! ```rust
+//! # use redb_model::{FieldChange, Model, ModelDiff};
+//! #[derive(Model, Clone)]
+//! #[model(diff)]
+//! struct User {
+//!     #[entry(position = "key")]
+//!     id: u32,
+//!     #[entry(position = "value")]
+//!     name: String,
+//! }
+//!
+//! let before = User { id: 0, name: String::from("a") };
+//! let after = User { name: String::from("b"), ..before.clone() };
+//! assert_eq!(
+//!     before.diff(&after),
+//!     [FieldChange {
+//!         field: "name",
+//!         before: String::from("\"a\""),
+//!         after: String::from("\"b\""),
+//!     }]
+//! );
+//! ```
+//!
+//! ### Batches
+//!
+//! `ModelTable::insert_many` and `ModelTable::upsert_many` insert any number of
+//! models into an open table, optionally sorted by key for locality within the
+//! B-tree, returning a [`BatchCount`] of the models inserted and replaced.
+//!
+//! ```rust
+//! # use redb::backends::InMemoryBackend;
+//! # use redb::Database;
+//! # use redb_model::{BatchCount, Model, ModelTable};
+//! # let db = Database::builder()
+//! #     .create_with_backend(InMemoryBackend::new())
+//! #     .unwrap();
+//! # #[derive(Model, Debug, PartialEq, Eq)]
+//! # #[model(impl_ext)]
+//! # struct User {
+//! #     #[entry(position = "key")]
+//! #     id: u32,
+//! #     #[entry(position = "value", redb_type = "&str")]
+//! #     name: String,
+//! # }
+//! let users = (0..1000).rev().map(|id| User { id, name: id.to_string() });
+//!
+//! let txn = db.begin_write().unwrap();
+//! {
+//!     let mut table = txn.open_table(User::DEFINITION).unwrap();
+//!     let count = table.insert_many(users, true).unwrap();
+//!     assert_eq!(count, BatchCount { inserted: 1000, replaced: 0 });
+//! }
+//! txn.commit().unwrap();
+//! ```
+//!
+//! ### Timestamps
+//!
+//! Fields declaring `created_at` are set when the model is inserted with
+//! `ModelTable::insert_model`, while fields declaring `updated_at` are also set
+//! when the model is updated with `ModelTable::update_model`. Replacing a stored
+//! model keeps the stored `created_at` fields. The field type must implement
+//! [`Timestamp`], implemented for `SystemTime`, `Duration` (since the unix epoch),
+//! and the `chrono` and `time` UTC date times behind their features. The `_with`
+//! variants of the operations accept a [`Clock`], allowing the time to be injected,
+//! for example in tests.
+//!
+//! ```rust
+//! # use redb_model::{Model, ModelExt};
+//! use std::time::SystemTime;
+//!
+//! #[derive(Model)]
+//! #[model(impl_ext)]
+//! struct Post {
+//!     #[entry(position = "key")]
+//!     id: u32,
+//!     #[entry(position = "value", adapter = "std_time", created_at)]
+//!     created: SystemTime,
+//!     #[entry(position = "value", adapter = "std_time", updated_at)]
+//!     updated: SystemTime,
+//! }
+//! ```
+//!
+//! ### Versioning
+//!
+//! A single `u64` value field may declare `version`, in which case
+//! `ModelTable::update_model` returns [`Error::Conflict`] if the stored version
+//! differs from the version of the model, and otherwise increments the version of
+//! the model before replacing the stored model. This allows a model read in one
+//! transaction to be safely updated in a later transaction.
+//!
+//! ```rust
+//! # use redb::backends::InMemoryBackend;
+//! # use redb::Database;
+//! # use redb_model::{Error, Model, ModelExt, ModelTable, ReadableModelTable};
+//! # let db = Database::builder()
+//! #     .create_with_backend(InMemoryBackend::new())
+//! #     .unwrap();
+//! #[derive(Model, Debug, PartialEq, Eq)]
+//! #[model(impl_ext)]
+//! struct Account {
+//!     #[entry(position = "key")]
+//!     id: u32,
+//!     #[entry(position = "value")]
+//!     balance: u64,
+//!     #[entry(position = "value", version)]
+//!     version: u64,
+//! }
+//!
+//! let txn = db.begin_write().unwrap();
+//! let mut table = txn.open_table(Account::DEFINITION).unwrap();
+//! table.insert_model(&mut Account { id: 0, balance: 0, version: 0 }).unwrap();
+//!
+//! let mut a = table.get_model::<Account>(0).unwrap().unwrap();
+//! let mut b = table.get_model::<Account>(0).unwrap().unwrap();
+//!
+//! a.balance += 10;
+//! table.update_model(&mut a).unwrap();
+//! assert_eq!(a.version, 1);
+//!
+//! b.balance += 20;
+//! assert!(matches!(
+//!     table.update_model(&mut b),
+//!     Err(Error::Conflict { expected: 0, found: 1 })
+//! ));
+//! ```
+//!
+//! ### Soft Delete
+//!
+//! Models declaring `soft_delete` store a trailing `bool` removal mark in the table
+//! value. `ModelTable::soft_remove_model` marks a model as removed, and
+//! `ModelTable::restore_model` clears the mark, neither of which compile for models
+//! without `soft_delete`. Soft removed models are skipped by
+//! `get_model`, `range_models` and `iter_models`, but are returned by their
+//! `_with_deleted` variants, and are permanently removed by
+//! `ModelTable::purge_models`, `ModelTable::remove_model` or `ModelTable::take_model`,
+//! which returns `None` for a soft removed model.
+//!
+//! ```rust
+//! # use redb::backends::InMemoryBackend;
+//! # use redb::Database;
+//! # use redb_model::{Model, ModelExt, ModelTable, ReadableModelTable};
+//! # let db = Database::builder()
+//! #     .create_with_backend(InMemoryBackend::new())
+//! #     .unwrap();
+//! #[derive(Model, Debug, PartialEq, Eq)]
+//! #[model(impl_ext, soft_delete)]
+//! struct User {
+//!     #[entry(position = "key")]
+//!     id: u32,
+//!     #[entry(position = "value", redb_type = "&str")]
+//!     name: String,
+//! }
+//!
+//! let txn = db.begin_write().unwrap();
+//! let mut table = txn.open_table(User::DEFINITION).unwrap();
+//! table.insert_model(&mut User { id: 0, name: String::from("name") }).unwrap();
+//!
+//! table.soft_remove_model::<User>(0).unwrap();
+//! assert_eq!(table.get_model::<User>(0).unwrap(), None);
+//! assert!(table.get_model_with_deleted::<User>(0).unwrap().is_some());
+//!
+//! assert_eq!(table.purge_models::<User>().unwrap(), 1);
+//! ```
+//!
+//! ## Hooks
+//!
+//! Models implementing [`ModelExt`] also implement [`ModelHooks`], called before a
+//! model is inserted or removed by the table operations, and after a model is
+//! instantiated by any of the `from_` methods. The derived hooks are no-op, unless
+//! `hooks` is declared, in which case the model must implement [`ModelHooks`].
+//!
+//! ```rust
+//! # use redb_model::{Model, ModelExt, ModelHooks};
+//! #[derive(Model)]
+//! #[model(impl_ext, hooks)]
+//! struct User {
+//!     #[entry(position = "key")]
+//!     id: u32,
+//!     #[entry(position = "value", redb_type = "&str")]
+//!     email: String,
+//! }
+//!
+//! impl ModelHooks for User {
+//!     fn before_insert(&mut self) {
+//!         self.email = self.email.to_lowercase();
+//!     }
+//! }
+//! ```
+//!
+//! ## Database
+//!
+//! A [`ModelDatabase`] creates the tables of all models registered with the
+//! [`ModelDatabaseBuilder`] when opened, so that read transactions never fail with
+//! `TableDoesNotExist`. Existing tables are validated against the key and value
+//! types of the models, and the schema of each model is registered with
+//! `schema::register`. The `ModelDatabase` dereferences to the `redb::Database`.
+//!
+//! ```rust,ignore
+//! let db = ModelDatabase::builder()
+//!     .register::<User>()
+//!     .register::<Edge>()
+//!     .open("app.redb")?;
+//! ```
+//!
+//! ### Schema Compatibility
+//!
+//! Each model has a `SCHEMA_HASH`, a fingerprint of the table name and type, the
+//! `redb` key and value types, and the name, position and `redb` type of each field
+//! in order. Types are compared by their normalized spelling, such that
+//! `std::string::String` and `String` are equal, while renaming a field changes the
+//! hash even though the stored layout is kept. Opening a `ModelDatabase` compares the hash of each model with the hash
+//! registered within the database, returning `Error::SchemaMismatch` if they differ,
+//! such as when fields of the same type are reordered. The check is also available
+//! as `schema::check`, while `schema::register` replaces the registered schema once
+//! the table is migrated.
+//!
+//! ## Transactions
+//!
+//! A [`WriteTxn`] or [`ReadTxn`] wraps a `redb` transaction, performing the model
+//! operations of any number of models without opening their tables. Each caches the
+//! table of each model for the lifetime of the transaction, such that the table of
+//! a model written with a `WriteTxn` may not also be opened directly.
+//!
+//! ```rust
+//! # use redb::backends::InMemoryBackend;
+//! # use redb::Database;
+//! # use redb_model::{Model, ReadTxn, WriteTxn};
+//! # let db = Database::builder()
+//! #     .create_with_backend(InMemoryBackend::new())
+//! #     .unwrap();
+//! #[derive(Model, Debug, Clone, PartialEq, Eq)]
+//! #[model(impl_ext)]
+//! struct User {
+//!     #[entry(position = "key")]
+//!     id: u32,
+//!     #[entry(position = "value", redb_type = "&str")]
+//!     name: String,
+//! }
+//!
+//! #[derive(Model, Debug, Clone, PartialEq, Eq)]
+//! #[model(impl_ext)]
+//! struct Follow {
+//!     #[entry(position = "key")]
+//!     source: u32,
+//!     #[entry(position = "key")]
+//!     target: u32,
+//! }
+//!
+//! let txn = WriteTxn::new(db.begin_write().unwrap());
+//! txn.insert(&mut User { id: 0, name: String::from("a") }).unwrap();
+//! txn.insert(&mut User { id: 1, name: String::from("b") }).unwrap();
+//! txn.insert(&mut Follow { source: 0, target: 1 }).unwrap();
+//! txn.commit().unwrap();
+//!
+//! let txn = ReadTxn::new(db.begin_read().unwrap());
+//! for follow in txn.range::<Follow, (u32, u32)>((0, 0)..(1, 0)).unwrap() {
+//!     let user = txn.get::<User>(follow.unwrap().target).unwrap();
+//!     assert_eq!(user.unwrap().name, "b");
+//! }
+//! ```
+//!
+//! ### Retrying Writes
+//!
+//! [`DatabaseExt::write`] runs a closure with a `WriteTxn`, committing the transaction
+//! if the closure succeeds, otherwise aborting it. Transactions failing with a
+//! version conflict or interrupted I/O are run again, as decided by a [`Retry`]
+//! policy given to `write_with`.
+//!
+//! ```rust
+//! # use redb::backends::InMemoryBackend;
+//! # use redb::Database;
+//! # use redb_model::{DatabaseExt, Error, Model, Retry};
+//! # let db = Database::builder()
+//! #     .create_with_backend(InMemoryBackend::new())
+//! #     .unwrap();
+//! #[derive(Model, Debug, Clone, PartialEq, Eq)]
+//! #[model(impl_ext)]
+//! struct Account {
+//!     #[entry(position = "key")]
+//!     id: u32,
+//!     #[entry(position = "value")]
+//!     balance: u64,
+//!     #[entry(position = "value", version)]
+//!     version: u64,
+//! }
+//!
+//! db.write(|txn| txn.insert(&mut Account { id: 0, balance: 0, version: 0 }))
+//!     .unwrap();
+//!
+//! let balance = db
+//!     .write_with(&Retry::new().attempts(5), |txn| {
+//!         let mut account = txn.get::<Account>(0)?.ok_or(Error::NotFound)?;
+//!         account.balance += 10;
+//!         txn.update(&mut account)?;
+//!         Ok(account.balance)
+//!     })
+//!     .unwrap();
+//! assert_eq!(balance, 10);
+//! ```
+//!
+//! ### History
+//!
+//! Models declaring `history` record each insertion, update or removal made with a
+//! `WriteTxn` in a history table, keyed by the model key and a sequence number
+//! increasing with each change to the table. The [`history`] module lists the
+//! changes to the model of a key, each with the model replaced or removed, and
+//! reconstructs the model of a key as of a sequence number. Imports and the
+//! [`EventStore`] record history as a `WriteTxn`, while the writes of
+//! [`ModelTable`] return `Error::HistoryNotRecorded`, being unable to record it.
+//!
+//! ```rust
+//! # use redb::backends::InMemoryBackend;
+//! # use redb::Database;
+//! # use redb_model::{history, Model, WriteTxn};
+//! # let db = Database::builder()
+//! #     .create_with_backend(InMemoryBackend::new())
+//! #     .unwrap();
+//! #[derive(Model, Debug, Clone, PartialEq, Eq)]
+//! #[model(impl_ext, history)]
+//! struct Account {
+//!     #[entry(position = "key")]
+//!     id: u32,
+//!     #[entry(position = "value")]
+//!     balance: u64,
+//! }
+//!
+//! let txn = WriteTxn::new(db.begin_write().unwrap());
+//! txn.insert(&mut Account { id: 0, balance: 10 }).unwrap();
+//! txn.update(&mut Account { id: 0, balance: 20 }).unwrap();
+//! txn.commit().unwrap();
+//!
+//! let txn = db.begin_read().unwrap();
+//! assert_eq!(history::history::<Account>(&txn, 0).unwrap().len(), 2);
+//! assert_eq!(
+//!     history::as_of::<Account>(&txn, 0, 1).unwrap(),
+//!     Some(Account { id: 0, balance: 10 })
+//! );
+//! ```
+//!
+//! ## Event Sourcing
+//!
+//! An [`EventStore`] appends event models, keyed by an aggregate id and sequence
+//! number, and rebuilds an aggregate implementing [`Aggregate`] by applying its
+//! events in order. Appending checks the expected version of the aggregate,
+//! returning `Error::Conflict` if another write appended first. Aggregates
+//! declaring a `version` field may be snapshotted every `n` events, such that
+//! loading only replays the later events. Events and snapshots are written within a
+//! `WriteTxn`, recording their history and changes.
+//!
+//! ```rust
+//! # use redb::backends::InMemoryBackend;
+//! # use redb::Database;
+//! # use redb_model::{Aggregate, EventStore, Model, WriteTxn};
+//! # let db = Database::builder()
+//! #     .create_with_backend(InMemoryBackend::new())
+//! #     .unwrap();
+//! #[derive(Model, Debug, Clone)]
+//! #[model(impl_ext)]
+//! struct Deposit {
+//!     #[entry(position = "key")]
+//!     account: u32,
+//!     #[entry(position = "key")]
+//!     seq: u64,
+//!     #[entry(position = "value")]
+//!     amount: u64,
+//! }
+//!
+//! #[derive(Model, Debug, Clone, Default)]
+//! #[model(impl_ext)]
+//! struct Account {
+//!     #[entry(position = "key")]
+//!     id: u32,
+//!     #[entry(position = "value")]
+//!     balance: u64,
+//!     #[entry(position = "value", version)]
+//!     version: u64,
+//! }
+//!
+//! impl Aggregate<Deposit> for Account {
+//!     fn apply(&mut self, event: &Deposit) {
+//!         self.balance += event.amount;
+//!     }
+//! }
+//!
+//! let store = EventStore::<Deposit, Account>::new().snapshot_every(100);
+//! let deposit = |amount| Deposit { account: 0, seq: 0, amount };
+//!
+//! let txn = WriteTxn::new(db.begin_write().unwrap());
+//! assert_eq!(store.append(&txn, 0, 0, [deposit(10), deposit(5)]).unwrap(), 2);
+//! assert!(store.append(&txn, 0, 0, [deposit(1)]).is_err());
+//! txn.commit().unwrap();
+//!
+//! let txn = db.begin_read().unwrap();
+//! let (account, version) = store.load(&txn, 0).unwrap();
+//! assert_eq!((account.balance, version), (15, 2));
+//! ```
+//!
+//! ## Change Notifications
+//!
+//! A [`WriteTxn`] wraps a `redb::WriteTransaction`, performing the [`ModelTable`]
+//! operations on the table of each model, and recording the changes made. Once the
+//! transaction is committed, each [`Change`] is sent to the receivers returned by
+//! [`Subscribers::subscribe`] for `Clone + Send` models stored in a table, of the
+//! subscribers the `WriteTxn` was created with. A [`ModelDatabase`] holds the
+//! subscribers of the database, publishing the changes of its `DatabaseExt`
+//! transactions. Changes of aborted transactions, or made directly on the `redb`
+//! tables, are not sent.
+//!
+//! ```rust
+//! # use redb::backends::InMemoryBackend;
+//! # use redb::Database;
+//! # use redb_model::{Change, Model, Subscribers, WriteTxn};
+//! # let db = Database::builder()
+//! #     .create_with_backend(InMemoryBackend::new())
+//! #     .unwrap();
+//! #[derive(Model, Debug, Clone, PartialEq, Eq)]
+//! #[model(impl_ext)]
+//! struct User {
+//!     #[entry(position = "key")]
+//!     id: u32,
+//!     #[entry(position = "value", redb_type = "&str")]
+//!     name: String,
+//! }
+//!
+//! let subscribers = Subscribers::new();
+//! let receiver = subscribers.subscribe::<User>();
+//! let mut user = User { id: 0, name: String::from("name") };
+//!
+//! let txn = WriteTxn::with_subscribers(db.begin_write().unwrap(), &subscribers);
+//! txn.insert(&mut user).unwrap();
+//! assert!(receiver.try_recv().is_err());
+//! txn.commit().unwrap();
+//!
+//! assert_eq!(receiver.try_recv(), Ok(Change::Insert(user)));
+//! ```
+//!
+//! ## Async
+//!
+//! With the `async` feature, an `AsyncModelDb` runs the model operations on blocking
+//! threads, each in its own transaction, returning futures of owned models. Reads run
+//! on a pool of threads, and writes on a dedicated thread, such that waiting writes
+//! never delay reads. The futures do not depend on any async runtime. Keys and ranges
+//! are encoded before being moved to a thread, and the `read` and `write` methods run
+//! a closure with a transaction, committing the write transaction if the closure
+//! succeeds.
+//!
+//! ```rust,ignore
+//! use redb_model::AsyncModelDb;
+//!
+//! let db = AsyncModelDb::new(Database::create("db.redb")?);
+//!
+//! db.insert(User { id: 0, name: String::from("name") }).await?;
+//! let user = db.get::<User>(0).await?;
+//! let users = db.range::<User, u32>(0..10).await?;
+//! ```
+//!
+//! ## Export and Import
+//!
+//! With the `serde` feature, the table of a `Serialize` model may be exported as
+//! JSON Lines with `export`, and `Deserialize` models imported with `import`. Models
+//! are decoded and encoded with the [`ModelExt`] methods, calling hooks and
+//! validation as for any insertion, although timestamps are retained. The `Json`
+//! options allow exporting a JSON array, reporting progress, and committing an
+//! import in batches.
+//!
+//! ```rust,ignore
+//! let txn = db.begin_read()?;
+//! redb_model::export::<User>(&txn, File::create("users.jsonl")?)?;
+//!
+//! let reader = BufReader::new(File::open("users.jsonl")?);
+//! redb_model::Json::new()
+//!     .batch_size(10_000)
+//!     .progress(|n| println!("imported {n} users"))
+//!     .import_batched::<User>(&db, reader)?;
+//! ```
+//!
+//! ### CSV
+//!
+//! Models declaring `csv` may be exported and imported as CSV with the [`Csv`]
+//! options, provided every field implements `ToString` and `FromStr`. The header
+//! row holds the field names, optionally marked as `key:<name>` or `value:<name>`,
+//! and columns are imported by name. The delimiter, batch size and progress
+//! callback are configurable.
+//!
+//! ```rust
+//! # use redb::backends::InMemoryBackend;
+//! # use redb::Database;
+//! # use redb_model::{Csv, Model, ModelExt, ModelTable};
+//! # let db = Database::builder()
+//! #     .create_with_backend(InMemoryBackend::new())
+//! #     .unwrap();
+//! #[derive(Model)]
+//! #[model(impl_ext, csv)]
+//! struct User {
+//!     #[entry(position = "key")]
+//!     id: u32,
+//!     #[entry(position = "value", redb_type = "&str")]
+//!     name: String,
+//! }
+//!
+//! let txn = db.begin_write().unwrap();
+//! txn.open_table(User::DEFINITION)
+//!     .unwrap()
+//!     .insert_model(&mut User { id: 0, name: String::from("name") })
+//!     .unwrap();
+//! txn.commit().unwrap();
+//!
+//! let mut buf = Vec::new();
+//! let txn = db.begin_read().unwrap();
+//! Csv::new()
+//!     .delimiter(';')
+//!     .markers(true)
+//!     .export::<User>(&txn, &mut buf)
+//!     .unwrap();
+//! assert_eq!(buf, b"key:id;value:name\n0;name\n");
+//! ```
+//!
+//! ## Inspection
+//!
+//! The [`schema`] module records the `Model::SCHEMA` of a model in the database with
+//! `schema::register`, holding the table name, type, and the name and type of each
+//! field. The `redb-model` binary reads this registry to list the tables of a
+//! database, describe their schema and print their rows without any model types.
+//! Rows are decoded by the `redb` types stored within the table, supporting primitive
+//! types, `&str`, `String`, `&[u8]`, `Option`, arrays, `Vec` and tuples of these, and
+//! printing fields of other types as hexadecimal. With `--json`, numbers and booleans
+//! are printed as JSON numbers and booleans, `None` as `null`, and tuples as arrays.
+//! Rows may be limited with `--from`, `--to` and `--limit`, taking keys as comma
+//! separated fields.
+//!
+//! ```text
+//! redb-model app.redb tables
+//! redb-model app.redb schema users --json
+//! redb-model app.redb rows users --from 10 --limit 5
+//! ```
+//!
+//! Fields of other types, such as those converted by an adapter, are printed in the
+//! CSV form of the model, rather than as hexadecimal, by registering it with an
+//! [`inspect::Inspector`] in a binary of your own.
+//!
+//! ```rust,ignore
+//! fn main() {
+//!     redb_model::inspect::Inspector::new()
+//!         .register::<User>()
+//!         .run(std::env::args().skip(1), &mut std::io::stdout())
+//!         .unwrap();
+//! }
+//! ```
+//!
+//! ## Testing
+//!
+//! With the `testing` feature, `testing::assert_roundtrip` asserts that models are
+//! returned unchanged by every `ModelExt` conversion, and by reading them from a
+//! table of an in-memory database. Models may be given by hand, or generated by the
+//! `Arbitrary` implementation of the model with the `proptest` or `quickcheck`
+//! features, which shrink a failing model to the simplest model failing.
+//!
+//! ```rust,ignore
+//! #[test]
+//! fn roundtrip() {
+//!     redb_model::testing::assert_roundtrip([
+//!         User { id: 0, name: String::from("") },
+//!         User { id: u32::MAX, name: String::from("name") },
+//!     ]);
+//! }
+//!
+//! #[test]
+//! fn generated_roundtrip() {
+//!     redb_model::testing::proptest::assert_roundtrip::<User>();
+//!     redb_model::testing::quickcheck::assert_roundtrip::<User>();
+//! }
+//! ```
+//!
 //! ## Type Aliases
 //!
 //! Generated definitions of the [`ModelExt`] traits defines type aliases for the
@@ -201,5 +934,37 @@
 //! `ModelExt::ModelKey` | A tuple of the owned key type(s) defined in the model.
 //! `ModelExt::ModelValue` | A tuple of the owned value type(s) defined in the model.
 //!
+pub mod adapter;
+#[cfg(feature = "async")]
+mod async_db;
+mod clock;
+mod db;
+mod error;
+mod event;
+pub mod history;
+pub mod inspect;
+mod notify;
+pub mod schema;
+mod table;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod transfer;
+mod txn;
+
 pub use _derive::Model;
-pub use _trait::{Model, ModelExt};
+pub use _trait::{
+    FieldChange, FieldSchema, Model, ModelDiff, ModelExt, ModelHooks, ModelPatch, ModelSchema,
+    TableModel, ValidationError,
+};
+#[cfg(feature = "async")]
+pub use async_db::{AsyncModelDb, Blocking};
+pub use clock::{Clock, SystemClock, Timestamp};
+pub use db::{DatabaseExt, ModelDatabase, ModelDatabaseBuilder, ModelDefinition, Retry};
+pub use error::Error;
+pub use event::{Aggregate, EventStore};
+pub use notify::{Change, Subscribers};
+pub use table::{BatchCount, ModelRange, ModelTable, ReadableModelTable};
+#[cfg(feature = "serde")]
+pub use transfer::{export, import, Json, JsonFormat};
+pub use transfer::{Csv, CsvField, CsvModel};
+pub use txn::{ReadTxn, WriteTxn};
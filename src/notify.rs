@@ -0,0 +1,65 @@
+//! Notifications of model changes.
+use std::any::{Any, TypeId};
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::TableModel;
+
+/// A change of a model, published after the transaction is committed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change<M> {
+    /// A model was inserted.
+    Insert(M),
+    /// A model was replaced.
+    Update {
+        /// The replaced model.
+        previous: M,
+        /// The inserted model.
+        model: M,
+    },
+    /// A model was removed.
+    Remove(M),
+}
+
+/// The subscribers to the changes of the models of a database, made through a
+/// [`WriteTxn`](crate::WriteTxn) created with the subscribers. Clones share the
+/// subscribers.
+#[derive(Clone, Default)]
+pub struct Subscribers {
+    /// Subscribers of each model type, as a `Vec<Sender<Change<M>>>`.
+    senders: Arc<Mutex<BTreeMap<TypeId, Box<dyn Any + Send>>>>,
+}
+
+impl Subscribers {
+    /// Create a new `Subscribers` without any subscribers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a receiver of the changes of `M` models of committed transactions. The
+    /// receiver is unsubscribed once dropped.
+    pub fn subscribe<M: TableModel + Clone + Send>(&self) -> Receiver<Change<M>> {
+        let (sender, receiver) = mpsc::channel();
+        let mut senders = self.senders.lock().unwrap_or_else(PoisonError::into_inner);
+        senders
+            .entry(TypeId::of::<M>())
+            .or_insert_with(|| Box::new(Vec::<Sender<Change<M>>>::new()))
+            .downcast_mut::<Vec<Sender<Change<M>>>>()
+            .expect("subscribers are keyed by model type")
+            .push(sender);
+
+        receiver
+    }
+
+    /// Send the change to all subscribers of `M`, dropping disconnected subscribers.
+    pub(crate) fn publish<M: Clone + Send + 'static>(&self, change: Change<M>) {
+        let mut senders = self.senders.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(senders) = senders
+            .get_mut(&TypeId::of::<M>())
+            .and_then(|senders| senders.downcast_mut::<Vec<Sender<Change<M>>>>())
+        {
+            senders.retain(|sender| sender.send(change.clone()).is_ok());
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! A registry of model schemas, persisted within the database.
+use redb::{ReadTransaction, ReadableTable, TableDefinition, TableError, WriteTransaction};
+
+use crate::{Error, Model, ModelSchema};
+
+/// The `redb` value of a registered schema: the schema hash, table type, key
+/// type, value type and fields.
+type SchemaValue = (
+    u64,
+    &'static str,
+    &'static str,
+    &'static str,
+    Vec<(&'static str, bool, &'static str, &'static str)>,
+);
+
+/// The table of registered schemas, keyed by table name.
+pub const SCHEMA_TABLE: TableDefinition<&str, SchemaValue> =
+    TableDefinition::new("redb_model_schema");
+
+/// A schema registered within the database. See [`ModelSchema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSchema {
+    /// The table name.
+    pub name: String,
+    /// The fingerprint of the schema. See [`ModelSchema::hash`].
+    pub hash: u64,
+    /// The table type, either `table` or `multimap`.
+    pub table_type: String,
+    /// The `redb` key type.
+    pub key_type: String,
+    /// The `redb` value type.
+    pub value_type: String,
+    /// The fields of the key, followed by the fields of the value, in tuple order.
+    pub fields: Vec<TableField>,
+}
+
+/// A field of a schema registered within the database. See
+/// [`FieldSchema`](crate::FieldSchema).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableField {
+    /// The field name.
+    pub name: String,
+    /// Whether the field is of the key, rather than the value.
+    pub key: bool,
+    /// The field type declared within the model.
+    pub ty: String,
+    /// The field type declared within the database.
+    pub redb_type: String,
+}
+
+impl From<ModelSchema> for TableSchema {
+    fn from(schema: ModelSchema) -> Self {
+        Self {
+            name: schema.name.to_owned(),
+            hash: schema.hash(),
+            table_type: schema.table_type.to_owned(),
+            key_type: schema.key_type.to_owned(),
+            value_type: schema.value_type.to_owned(),
+            fields: schema
+                .fields
+                .iter()
+                .map(|field| TableField {
+                    name: field.name.to_owned(),
+                    key: field.key,
+                    ty: field.ty.to_owned(),
+                    redb_type: field.redb_type.to_owned(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Register the schema of `M`, replacing any schema registered for the table.
+pub fn register<'a, M: Model<'a>>(txn: &WriteTransaction) -> Result<(), Error> {
+    let schema = M::SCHEMA;
+    let fields = schema
+        .fields
+        .iter()
+        .map(|field| (field.name, field.key, field.ty, field.redb_type))
+        .collect();
+
+    let mut table = txn.open_table(SCHEMA_TABLE)?;
+    table.insert(
+        schema.name,
+        (
+            M::SCHEMA_HASH,
+            schema.table_type,
+            schema.key_type,
+            schema.value_type,
+            fields,
+        ),
+    )?;
+    Ok(())
+}
+
+/// Check that the schema registered for the table of `M`, if any, has the
+/// `SCHEMA_HASH` of `M`. Returns `Error::SchemaMismatch` if the model differs from
+/// the registered schema, such as by reordering fields.
+pub fn check<'a, M: Model<'a>>(txn: &ReadTransaction) -> Result<(), Error> {
+    match txn.open_table(SCHEMA_TABLE) {
+        Ok(table) => check_table::<M>(&table),
+        Err(TableError::TableDoesNotExist(_)) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Check, then register the schema of `M`. See `check` and `register`.
+pub(crate) fn check_and_register<'a, M: Model<'a>>(txn: &WriteTransaction) -> Result<(), Error> {
+    check_table::<M>(&txn.open_table(SCHEMA_TABLE)?)?;
+    register::<M>(txn)
+}
+
+/// Check the schema of `M` registered within the table.
+fn check_table<'a, M: Model<'a>>(
+    table: &impl ReadableTable<&'static str, SchemaValue>,
+) -> Result<(), Error> {
+    let name = M::SCHEMA.name;
+    match table.get(name)?.map(|guard| guard.value().0) {
+        Some(found) if found != M::SCHEMA_HASH => Err(Error::SchemaMismatch {
+            table: name.to_owned(),
+            expected: M::SCHEMA_HASH,
+            found,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Get the schema registered for the table of the given name, if any.
+pub fn get(txn: &ReadTransaction, name: &str) -> Result<Option<TableSchema>, Error> {
+    let table = match txn.open_table(SCHEMA_TABLE) {
+        Ok(table) => table,
+        Err(TableError::TableDoesNotExist(_)) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(table.get(name)?.map(|guard| schema(name, guard.value())))
+}
+
+/// Get all registered schemas, ordered by table name.
+pub fn all(txn: &ReadTransaction) -> Result<Vec<TableSchema>, Error> {
+    let table = match txn.open_table(SCHEMA_TABLE) {
+        Ok(table) => table,
+        Err(TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    table
+        .iter()?
+        .map(|entry| {
+            let (name, value) = entry?;
+            Ok(schema(name.value(), value.value()))
+        })
+        .collect()
+}
+
+/// Instantiate a `TableSchema` from the registered value.
+fn schema(name: &str, value: <SchemaValue as redb::Value>::SelfType<'_>) -> TableSchema {
+    let (hash, table_type, key_type, value_type, fields) = value;
+
+    TableSchema {
+        name: name.to_owned(),
+        hash,
+        table_type: table_type.to_owned(),
+        key_type: key_type.to_owned(),
+        value_type: value_type.to_owned(),
+        fields: fields
+            .into_iter()
+            .map(|(name, key, ty, redb_type)| TableField {
+                name: name.to_owned(),
+                key,
+                ty: ty.to_owned(),
+                redb_type: redb_type.to_owned(),
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,852 @@
+//! Model operations on `redb` tables.
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+use std::ops::RangeBounds;
+
+use redb::{AccessGuard, Key, ReadableTable, Table, Value};
+
+use crate::{Clock, Error, ModelExt, ModelPatch, SystemClock};
+
+/// Read operations on a table of `M` models. Models removed by
+/// `ModelTable::soft_remove_model` are skipped, except by the `_with_deleted`
+/// variants.
+pub trait ReadableModelTable<K: Key + 'static, V: Value + 'static> {
+    /// Get the model of the given key.
+    fn get_model<'k, M>(&self, key: impl Borrow<K::SelfType<'k>>) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>;
+
+    /// Get the model of the given key, including a soft removed model.
+    fn get_model_with_deleted<'k, M>(
+        &self,
+        key: impl Borrow<K::SelfType<'k>>,
+    ) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>;
+
+    /// Get an iterator of the models within the given key range.
+    fn range_models<'a, M, KR>(
+        &self,
+        range: impl RangeBounds<KR> + 'a,
+    ) -> Result<ModelRange<'_, K, V, M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+        KR: Borrow<K::SelfType<'a>> + 'a;
+
+    /// Get an iterator of the models within the given key range, including soft
+    /// removed models.
+    fn range_models_with_deleted<'a, M, KR>(
+        &self,
+        range: impl RangeBounds<KR> + 'a,
+    ) -> Result<ModelRange<'_, K, V, M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+        KR: Borrow<K::SelfType<'a>> + 'a;
+
+    /// Get an iterator of all models in the table.
+    fn iter_models<M>(&self) -> Result<ModelRange<'_, K, V, M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        self.range_models::<M, K::SelfType<'_>>(..)
+    }
+
+    /// Get an iterator of all models in the table, including soft removed models.
+    fn iter_models_with_deleted<M>(&self) -> Result<ModelRange<'_, K, V, M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        self.range_models_with_deleted::<M, K::SelfType<'_>>(..)
+    }
+}
+
+impl<K, V, T> ReadableModelTable<K, V> for T
+where
+    K: Key + 'static,
+    V: Value + 'static,
+    T: ReadableTable<K, V>,
+{
+    fn get_model<'k, M>(&self, key: impl Borrow<K::SelfType<'k>>) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        get(self, key.borrow(), false)
+    }
+
+    fn get_model_with_deleted<'k, M>(
+        &self,
+        key: impl Borrow<K::SelfType<'k>>,
+    ) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        get(self, key.borrow(), true)
+    }
+
+    fn range_models<'a, M, KR>(
+        &self,
+        range: impl RangeBounds<KR> + 'a,
+    ) -> Result<ModelRange<'_, K, V, M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+        KR: Borrow<K::SelfType<'a>> + 'a,
+    {
+        Ok(ModelRange::new(self.range(range)?, false))
+    }
+
+    fn range_models_with_deleted<'a, M, KR>(
+        &self,
+        range: impl RangeBounds<KR> + 'a,
+    ) -> Result<ModelRange<'_, K, V, M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+        KR: Borrow<K::SelfType<'a>> + 'a,
+    {
+        Ok(ModelRange::new(self.range(range)?, true))
+    }
+}
+
+/// Get the model of the given key, skipping a soft removed model unless
+/// `with_deleted` is set.
+fn get<K, V, M>(
+    table: &impl ReadableTable<K, V>,
+    key: &K::SelfType<'_>,
+    with_deleted: bool,
+) -> Result<Option<M>, Error>
+where
+    K: Key + 'static,
+    V: Value + 'static,
+    M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+{
+    let Some(guard) = table.get(key)? else {
+        return Ok(None);
+    };
+    if !with_deleted && M::is_deleted(guard.value()) {
+        return Ok(None);
+    }
+
+    // Borrow the key for the lifetime of the guard.
+    let bytes = K::as_bytes(key);
+    let key = K::from_bytes(bytes.as_ref());
+
+    Ok(Some(M::from_key_and_guard((key, &guard))))
+}
+
+/// Write operations on a table of `M` models. Insertion calls
+/// `ModelHooks::before_insert` and validates the model, while removal calls
+/// `ModelHooks::before_remove`. The `created_at` and `updated_at` fields are
+/// set by the `SystemClock`, or the given `Clock` of the `_with` variants, while
+/// the `created_at` fields of a replaced model are kept.
+/// Soft removed models are treated as absent, except by `remove_model`,
+/// `restore_model` and `purge_models`.
+///
+/// Writes of models declaring `history` return `Error::HistoryNotRecorded`, as
+/// the table cannot record their history, except `purge_models`, as removals are
+/// recorded when soft removed. Such models are written with a `WriteTxn`.
+pub trait ModelTable<K: Key + 'static, V: Value + 'static> {
+    /// Insert the model, returning the replaced model, if any.
+    fn insert_model<M>(&mut self, model: &mut M) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        self.insert_model_with(model, &SystemClock)
+    }
+
+    /// Insert the model, setting timestamps with the given `Clock`.
+    fn insert_model_with<M>(
+        &mut self,
+        model: &mut M,
+        clock: &impl Clock,
+    ) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>;
+
+    /// Replace an existing model, returning the replaced model. Returns
+    /// `Error::NotFound` if no model exists for the key. For models declaring a
+    /// `version` field, returns `Error::Conflict` if the stored version differs,
+    /// otherwise increments the version of the model.
+    fn update_model<M>(&mut self, model: &mut M) -> Result<M, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        self.update_model_with(model, &SystemClock)
+    }
+
+    /// Replace an existing model, setting timestamps with the given `Clock`.
+    fn update_model_with<M>(&mut self, model: &mut M, clock: &impl Clock) -> Result<M, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>;
+
+    /// Insert the model as given, returning the replaced model, if any. Unlike
+    /// `insert_model`, hooks are not called, and the model is neither validated
+    /// nor timestamped, such as to restore a previous model for an undo.
+    fn replace_model<M>(&mut self, model: &M) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>;
+
+    /// Remove the model of the given key, returning the removed model, if any.
+    /// Unlike `remove_model`, `ModelHooks::before_remove` is not called, and the
+    /// removed model is decoded from the removed value without a prior lookup.
+    /// A soft removed model is removed, returning `None`, as with `replace_model`.
+    fn take_model<'k, M>(&mut self, key: impl Borrow<K::SelfType<'k>>) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>;
+
+    /// Insert the models, replacing any stored models, as with `insert_model`.
+    /// Unlike `insert_model`, stored models are not read, such that the
+    /// `created_at` fields of models replacing a stored model are set.
+    /// Sorts the models by key before insertion if `sort` is set. Returns the
+    /// number of models inserted and replaced.
+    fn insert_many<M>(
+        &mut self,
+        models: impl IntoIterator<Item = M>,
+        sort: bool,
+    ) -> Result<BatchCount, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        self.insert_many_with(models, sort, &SystemClock)
+    }
+
+    /// Insert the models, setting timestamps with the given `Clock`.
+    fn insert_many_with<M>(
+        &mut self,
+        models: impl IntoIterator<Item = M>,
+        sort: bool,
+        clock: &impl Clock,
+    ) -> Result<BatchCount, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>;
+
+    /// Insert the models, or replace the stored models of the same key. Unlike
+    /// `insert_many`, stored models are read, such that models replacing a stored
+    /// model keep the `created_at` fields of the stored model, as `update_model`.
+    /// Sorts the models by key before insertion if `sort` is set. Returns the
+    /// number of models inserted and replaced.
+    fn upsert_many<M>(
+        &mut self,
+        models: impl IntoIterator<Item = M>,
+        sort: bool,
+    ) -> Result<BatchCount, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        self.upsert_many_with(models, sort, &SystemClock)
+    }
+
+    /// Insert or replace the models, setting timestamps with the given `Clock`.
+    fn upsert_many_with<M>(
+        &mut self,
+        models: impl IntoIterator<Item = M>,
+        sort: bool,
+        clock: &impl Clock,
+    ) -> Result<BatchCount, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>;
+
+    /// Apply the patch to the model of the given key, and replace the model as
+    /// with `update_model`, returning the patched model. Returns
+    /// `Error::NotFound` if no model exists for the key.
+    fn patch_model<'k, M>(
+        &mut self,
+        key: impl Borrow<K::SelfType<'k>>,
+        patch: M::Patch,
+    ) -> Result<M, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V> + ModelPatch,
+    {
+        self.patch_model_with(key, patch, &SystemClock)
+    }
+
+    /// Apply the patch to the model of the given key, setting timestamps with the
+    /// given `Clock`.
+    fn patch_model_with<'k, M>(
+        &mut self,
+        key: impl Borrow<K::SelfType<'k>>,
+        patch: M::Patch,
+        clock: &impl Clock,
+    ) -> Result<M, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V> + ModelPatch;
+
+    /// Remove the model of the given key, returning the removed model, if any.
+    fn remove_model<'k, M>(
+        &mut self,
+        key: impl Borrow<K::SelfType<'k>>,
+    ) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>;
+
+    /// Mark the model of the given key as removed, returning the removed model,
+    /// if any. The model is kept in the table until purged. Fails to compile for
+    /// models without `soft_delete`.
+    ///
+    /// ```compile_fail
+    /// # use redb::backends::InMemoryBackend;
+    /// # use redb::Database;
+    /// # use redb_model::{Model, ModelTable};
+    /// #[derive(Model)]
+    /// #[model(impl_ext)]
+    /// struct Plain {
+    ///     #[entry(position = "key")]
+    ///     id: u32,
+    ///     #[entry(position = "value")]
+    ///     value: u32,
+    /// }
+    ///
+    /// # let db = Database::builder().create_with_backend(InMemoryBackend::new()).unwrap();
+    /// let txn = db.begin_write().unwrap();
+    /// let mut table = txn.open_table(Plain::DEFINITION).unwrap();
+    /// table.soft_remove_model::<Plain>(0).unwrap();
+    /// ```
+    fn soft_remove_model<'k, M>(
+        &mut self,
+        key: impl Borrow<K::SelfType<'k>>,
+    ) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>;
+
+    /// Restore the soft removed model of the given key, returning the restored
+    /// model, if any. Fails to compile for models without `soft_delete`.
+    fn restore_model<'k, M>(
+        &mut self,
+        key: impl Borrow<K::SelfType<'k>>,
+    ) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>;
+
+    /// Remove all soft removed models, returning the number of models removed.
+    fn purge_models<M>(&mut self) -> Result<u64, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>;
+}
+
+impl<K, V> ModelTable<K, V> for Table<'_, K, V>
+where
+    K: Key + 'static,
+    V: Value + 'static,
+{
+    fn insert_model_with<M>(
+        &mut self,
+        model: &mut M,
+        clock: &impl Clock,
+    ) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        unrecorded::<M>()?;
+        Unrecorded(self).insert_model_with(model, clock)
+    }
+
+    fn update_model_with<M>(&mut self, model: &mut M, clock: &impl Clock) -> Result<M, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        unrecorded::<M>()?;
+        Unrecorded(self).update_model_with(model, clock)
+    }
+
+    fn replace_model<M>(&mut self, model: &M) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        unrecorded::<M>()?;
+        Unrecorded(self).replace_model(model)
+    }
+
+    fn take_model<'k, M>(&mut self, key: impl Borrow<K::SelfType<'k>>) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        unrecorded::<M>()?;
+        Unrecorded(self).take_model(key)
+    }
+
+    fn insert_many_with<M>(
+        &mut self,
+        models: impl IntoIterator<Item = M>,
+        sort: bool,
+        clock: &impl Clock,
+    ) -> Result<BatchCount, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        unrecorded::<M>()?;
+        Unrecorded(self).insert_many_with(models, sort, clock)
+    }
+
+    fn upsert_many_with<M>(
+        &mut self,
+        models: impl IntoIterator<Item = M>,
+        sort: bool,
+        clock: &impl Clock,
+    ) -> Result<BatchCount, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        unrecorded::<M>()?;
+        Unrecorded(self).upsert_many_with(models, sort, clock)
+    }
+
+    fn patch_model_with<'k, M>(
+        &mut self,
+        key: impl Borrow<K::SelfType<'k>>,
+        patch: M::Patch,
+        clock: &impl Clock,
+    ) -> Result<M, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V> + ModelPatch,
+    {
+        unrecorded::<M>()?;
+        Unrecorded(self).patch_model_with(key, patch, clock)
+    }
+
+    fn remove_model<'k, M>(&mut self, key: impl Borrow<K::SelfType<'k>>) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        unrecorded::<M>()?;
+        Unrecorded(self).remove_model(key)
+    }
+
+    fn soft_remove_model<'k, M>(
+        &mut self,
+        key: impl Borrow<K::SelfType<'k>>,
+    ) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        unrecorded::<M>()?;
+        Unrecorded(self).soft_remove_model(key)
+    }
+
+    fn restore_model<'k, M>(
+        &mut self,
+        key: impl Borrow<K::SelfType<'k>>,
+    ) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        unrecorded::<M>()?;
+        Unrecorded(self).restore_model(key)
+    }
+
+    fn purge_models<M>(&mut self) -> Result<u64, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        Unrecorded(self).purge_models::<M>()
+    }
+}
+
+/// Returns `Error::HistoryNotRecorded` if `M` declares a history table, which a
+/// write of the table would not record.
+fn unrecorded<M>() -> Result<(), Error>
+where
+    M: for<'m> ModelExt<'m>,
+{
+    match M::HISTORY {
+        Some(history) => Err(Error::HistoryNotRecorded { history }),
+        None => Ok(()),
+    }
+}
+
+/// A table written without recording the history of models declaring `history`,
+/// for writes recording the history themselves, such as those of a `WriteTxn`.
+pub(crate) struct Unrecorded<'a, 't, K: Key + 'static, V: Value + 'static>(
+    pub(crate) &'a mut Table<'t, K, V>,
+);
+
+impl<K, V> ModelTable<K, V> for Unrecorded<'_, '_, K, V>
+where
+    K: Key + 'static,
+    V: Value + 'static,
+{
+    fn insert_model_with<M>(
+        &mut self,
+        model: &mut M,
+        clock: &impl Clock,
+    ) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        let now = clock.now();
+        model.set_created_at(now);
+        model.set_updated_at(now);
+        if let Some(stored) = self.0.get_model::<M>(model.as_key())? {
+            model.copy_created_at(&stored);
+        }
+
+        insert(self.0, model)
+    }
+
+    fn update_model_with<M>(&mut self, model: &mut M, clock: &impl Clock) -> Result<M, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        let Some(stored) = self.0.get_model::<M>(model.as_key())? else {
+            return Err(Error::NotFound);
+        };
+        model.copy_created_at(&stored);
+        let Some(expected) = model.version() else {
+            model.set_updated_at(clock.now());
+            return insert(self.0, model)?.ok_or(Error::NotFound);
+        };
+
+        let found = stored.version().unwrap_or_default();
+        if found != expected {
+            return Err(Error::Conflict { expected, found });
+        }
+        model.set_updated_at(clock.now());
+        model.set_version(expected + 1);
+
+        insert(self.0, model)
+            .inspect_err(|_| model.set_version(expected))?
+            .ok_or(Error::NotFound)
+    }
+
+    fn replace_model<M>(&mut self, model: &M) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        replace(self.0, model)
+    }
+
+    fn take_model<'k, M>(&mut self, key: impl Borrow<K::SelfType<'k>>) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        let key = key.borrow();
+        let guard = self.0.remove(key)?;
+
+        // Borrow the key for the lifetime of the guard.
+        let bytes = K::as_bytes(key);
+        let key = K::from_bytes(bytes.as_ref());
+
+        Ok(guard
+            .filter(|guard| !M::is_deleted(guard.value()))
+            .map(|guard| M::from_key_and_guard((key, &guard))))
+    }
+
+    fn insert_many_with<M>(
+        &mut self,
+        models: impl IntoIterator<Item = M>,
+        sort: bool,
+        clock: &impl Clock,
+    ) -> Result<BatchCount, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        insert_many(self.0, models, sort, clock, |_, _| Ok(()))
+    }
+
+    fn upsert_many_with<M>(
+        &mut self,
+        models: impl IntoIterator<Item = M>,
+        sort: bool,
+        clock: &impl Clock,
+    ) -> Result<BatchCount, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        upsert_many(self.0, models, sort, clock, |_, _| Ok(()))
+    }
+
+    fn patch_model_with<'k, M>(
+        &mut self,
+        key: impl Borrow<K::SelfType<'k>>,
+        patch: M::Patch,
+        clock: &impl Clock,
+    ) -> Result<M, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V> + ModelPatch,
+    {
+        let mut model = self.0.get_model::<M>(key)?.ok_or(Error::NotFound)?;
+        model.apply_patch(patch);
+        self.update_model_with(&mut model, clock)?;
+
+        Ok(model)
+    }
+
+    fn remove_model<'k, M>(&mut self, key: impl Borrow<K::SelfType<'k>>) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        let Some(model) = self.0.get_model_with_deleted::<M>(key.borrow())? else {
+            return Ok(None);
+        };
+        model.before_remove();
+        self.0.remove(key)?;
+
+        Ok(Some(model))
+    }
+
+    fn soft_remove_model<'k, M>(
+        &mut self,
+        key: impl Borrow<K::SelfType<'k>>,
+    ) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        set_deleted(self.0, key.borrow(), true)
+    }
+
+    fn restore_model<'k, M>(
+        &mut self,
+        key: impl Borrow<K::SelfType<'k>>,
+    ) -> Result<Option<M>, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        set_deleted(self.0, key.borrow(), false)
+    }
+
+    fn purge_models<M>(&mut self) -> Result<u64, Error>
+    where
+        M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+    {
+        let mut purged = 0;
+        for entry in self.0.extract_if(|_, v| M::is_deleted(v))? {
+            let (k, v) = entry?;
+            M::from_guards((&k, &v)).before_remove();
+            purged += 1;
+        }
+
+        Ok(purged)
+    }
+}
+
+/// Call `ModelHooks::before_insert`, validate and insert the model, returning
+/// the replaced model, if any.
+pub(crate) fn insert<K, V, M>(
+    table: &mut Table<'_, K, V>,
+    model: &mut M,
+) -> Result<Option<M>, Error>
+where
+    K: Key + 'static,
+    V: Value + 'static,
+    M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+{
+    model.before_insert();
+    model.validate()?;
+
+    replace(table, model)
+}
+
+/// Insert the model, returning the replaced model, if any.
+fn replace<K, V, M>(table: &mut Table<'_, K, V>, model: &M) -> Result<Option<M>, Error>
+where
+    K: Key + 'static,
+    V: Value + 'static,
+    M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+{
+    let (k, v) = model.as_key_and_value();
+    let guard = table.insert(&k, v)?;
+
+    Ok(guard
+        .filter(|guard| !M::is_deleted(guard.value()))
+        .map(|guard| M::from_key_and_guard((k, &guard))))
+}
+
+/// The number of models inserted and replaced by `ModelTable::insert_many` or
+/// `ModelTable::upsert_many`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchCount {
+    /// The number of models inserted without replacing a stored model.
+    pub inserted: u64,
+    /// The number of models replacing a stored model.
+    pub replaced: u64,
+}
+
+/// Insert the models as `ModelTable::insert_many_with`, calling `inserted` with
+/// each model inserted and the value replaced, if any.
+pub(crate) fn insert_many<K, V, M>(
+    table: &mut Table<'_, K, V>,
+    models: impl IntoIterator<Item = M>,
+    sort: bool,
+    clock: &impl Clock,
+    inserted: impl FnMut(&M, Option<AccessGuard<'_, V>>) -> Result<(), Error>,
+) -> Result<BatchCount, Error>
+where
+    K: Key + 'static,
+    V: Value + 'static,
+    M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+{
+    let now = clock.now();
+    let prepare = |_: &Table<'_, K, V>, model: &mut M| {
+        model.set_created_at(now);
+        model.set_updated_at(now);
+        Ok(())
+    };
+
+    insert_batch(table, models, sort, prepare, inserted)
+}
+
+/// Insert or replace the models as `ModelTable::upsert_many_with`, calling
+/// `inserted` with each model inserted and the value replaced, if any.
+pub(crate) fn upsert_many<K, V, M>(
+    table: &mut Table<'_, K, V>,
+    models: impl IntoIterator<Item = M>,
+    sort: bool,
+    clock: &impl Clock,
+    inserted: impl FnMut(&M, Option<AccessGuard<'_, V>>) -> Result<(), Error>,
+) -> Result<BatchCount, Error>
+where
+    K: Key + 'static,
+    V: Value + 'static,
+    M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+{
+    let now = clock.now();
+    let prepare = |table: &Table<'_, K, V>, model: &mut M| {
+        match table.get_model::<M>(model.as_key())? {
+            Some(stored) => model.copy_created_at(&stored),
+            None => model.set_created_at(now),
+        }
+        model.set_updated_at(now);
+        Ok(())
+    };
+
+    insert_batch(table, models, sort, prepare, inserted)
+}
+
+/// Prepare and insert each model, optionally sorted by key, counting the models
+/// inserted and replaced.
+fn insert_batch<K, V, M>(
+    table: &mut Table<'_, K, V>,
+    models: impl IntoIterator<Item = M>,
+    sort: bool,
+    mut prepare: impl FnMut(&Table<'_, K, V>, &mut M) -> Result<(), Error>,
+    mut inserted: impl FnMut(&M, Option<AccessGuard<'_, V>>) -> Result<(), Error>,
+) -> Result<BatchCount, Error>
+where
+    K: Key + 'static,
+    V: Value + 'static,
+    M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+{
+    let mut models = models.into_iter().collect::<Vec<_>>();
+    if sort {
+        models.sort_by(|a, b| compare_keys::<K>(&a.as_key(), &b.as_key()));
+    }
+
+    let mut count = BatchCount::default();
+    for mut model in models {
+        prepare(table, &mut model)?;
+        model.before_insert();
+        let (k, v) = model.try_as_key_and_value()?;
+        let replaced = table
+            .insert(k, v)?
+            .filter(|guard| !M::is_deleted(guard.value()));
+        match replaced {
+            Some(_) => count.replaced += 1,
+            None => count.inserted += 1,
+        }
+        inserted(&model, replaced)?;
+    }
+
+    Ok(count)
+}
+
+/// Compare keys in the order of the `redb` table.
+fn compare_keys<K: Key>(a: &K::SelfType<'_>, b: &K::SelfType<'_>) -> Ordering {
+    K::compare(K::as_bytes(a).as_ref(), K::as_bytes(b).as_ref())
+}
+
+/// Set the removal mark of the model of the given key, returning the model if
+/// the mark changed. Calls `ModelHooks::before_remove` when marking as removed.
+/// Fails to compile for models without `soft_delete`.
+fn set_deleted<K, V, M>(
+    table: &mut Table<'_, K, V>,
+    key: &K::SelfType<'_>,
+    deleted: bool,
+) -> Result<Option<M>, Error>
+where
+    K: Key + 'static,
+    V: Value + 'static,
+    M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+{
+    const { assert!(M::SOFT_DELETE, "the model does not declare `soft_delete`") };
+
+    let (model, value) = {
+        let Some(guard) = table.get(key)? else {
+            return Ok(None);
+        };
+        if M::is_deleted(guard.value()) == deleted {
+            return Ok(None);
+        }
+
+        // Borrow the key for the lifetime of the guard.
+        let bytes = K::as_bytes(key);
+        let model = M::from_key_and_guard((K::from_bytes(bytes.as_ref()), &guard));
+        let value = V::as_bytes(&M::with_deleted(guard.value(), deleted))
+            .as_ref()
+            .to_vec();
+
+        (model, value)
+    };
+    if deleted {
+        model.before_remove();
+    }
+    table.insert(key, V::from_bytes(&value))?;
+
+    Ok(Some(model))
+}
+
+/// An iterator of models within a table range.
+pub struct ModelRange<'a, K: Key + 'static, V: Value + 'static, M> {
+    range: redb::Range<'a, K, V>,
+    with_deleted: bool,
+    _model: PhantomData<M>,
+}
+
+impl<'a, K: Key + 'static, V: Value + 'static, M> ModelRange<'a, K, V, M> {
+    /// Create an iterator of the models of a `redb` range, optionally including
+    /// soft removed models.
+    pub(crate) fn new(range: redb::Range<'a, K, V>, with_deleted: bool) -> Self {
+        Self {
+            range,
+            with_deleted,
+            _model: PhantomData,
+        }
+    }
+}
+
+impl<K, V, M> Iterator for ModelRange<'_, K, V, M>
+where
+    K: Key + 'static,
+    V: Value + 'static,
+    M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+{
+    type Item = Result<M, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.range.next()? {
+                Ok((k, v)) if self.with_deleted || !M::is_deleted(v.value()) => {
+                    return Some(Ok(M::from_guards((&k, &v))))
+                }
+                Ok(_) => continue,
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+    }
+}
+
+impl<K, V, M> DoubleEndedIterator for ModelRange<'_, K, V, M>
+where
+    K: Key + 'static,
+    V: Value + 'static,
+    M: for<'m> ModelExt<'m, RedbKey = K, RedbValue = V>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.range.next_back()? {
+                Ok((k, v)) if self.with_deleted || !M::is_deleted(v.value()) => {
+                    return Some(Ok(M::from_guards((&k, &v))))
+                }
+                Ok(_) => continue,
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+    }
+}
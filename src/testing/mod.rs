@@ -0,0 +1,161 @@
+//! Round-trip assertions for models, for use in tests.
+//!
+//! Models may be given by hand to [`assert_roundtrip`], or generated by the
+//! `Arbitrary` implementation of the model with the `proptest` or `quickcheck`
+//! features, shrinking a failing model to the simplest model failing:
+//!
+//! ```rust,ignore
+//! #[test]
+//! fn roundtrip() {
+//!     redb_model::testing::proptest::assert_roundtrip::<User>();
+//! }
+//! ```
+use std::fmt::Debug;
+
+use redb::backends::InMemoryBackend;
+use redb::{Database, ReadableTable, Value};
+
+use crate::{Model, ReadableModelTable, TableModel};
+
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "quickcheck")]
+pub mod quickcheck;
+
+/// Assert that each model is returned unchanged by every `ModelExt` conversion,
+/// and by every `ReadableModelTable` read of an in-memory `redb` database.
+///
+/// Each model is written within its own transaction, which is aborted once
+/// asserted, so models may share keys. Models with `after_load` hooks altering
+/// the model will not compare equal.
+///
+/// # Panics
+///
+/// Panics with the path and models compared, if any model differs.
+pub fn assert_roundtrip<M>(models: impl IntoIterator<Item = M>)
+where
+    M: TableModel + PartialEq + Debug,
+{
+    let db = database();
+    for model in models {
+        assert_model_roundtrip(&db, &model);
+    }
+}
+
+/// Create an in-memory database.
+fn database() -> Database {
+    Database::builder()
+        .create_with_backend(InMemoryBackend::new())
+        .expect("failed to create an in-memory database")
+}
+
+/// Assert the conversions of the model, and the reads of the model written to a
+/// table of the database.
+fn assert_model_roundtrip<M>(db: &Database, model: &M)
+where
+    M: TableModel + PartialEq + Debug,
+{
+    assert_conversions(model);
+    assert_table(db, model);
+}
+
+/// Assert the conversions of the model, without a database.
+fn assert_conversions<M>(model: &M)
+where
+    M: TableModel + PartialEq + Debug,
+{
+    assert_model(
+        "from_values",
+        model,
+        &M::from_values(model.as_key_and_value()),
+    );
+    assert_model(
+        "from_values(as_key, as_value)",
+        model,
+        &M::from_values((model.as_key(), model.as_value())),
+    );
+    assert_bytes::<M::Key>("as_key", model, model.as_key_and_value().0, model.as_key());
+    assert_bytes::<M::Value>(
+        "as_value",
+        model,
+        model.as_key_and_value().1,
+        model.as_value(),
+    );
+}
+
+/// Assert the reads of the model, written to a table of the database.
+fn assert_table<M>(db: &Database, model: &M)
+where
+    M: TableModel + PartialEq + Debug,
+{
+    let txn = db.begin_write().expect("failed to begin a transaction");
+    {
+        let mut table = txn
+            .open_table(<M as Model>::DEFINITION)
+            .expect("failed to open the table");
+        let (k, v) = model.as_key_and_value();
+        table.insert(k, v).expect("failed to insert the model");
+
+        let v_guard = table
+            .get(model.as_key())
+            .expect("failed to get the model")
+            .expect("the model was not inserted");
+        assert_model(
+            "from_key_and_guard",
+            model,
+            &M::from_key_and_guard((model.as_key(), &v_guard)),
+        );
+        drop(v_guard);
+
+        let mut range = table
+            .range(model.as_key()..=model.as_key())
+            .expect("failed to range the table");
+        let (k_guard, v_guard) = range
+            .next()
+            .expect("the model was not inserted")
+            .expect("failed to read the model");
+        assert_model("from_guards", model, &M::from_guards((&k_guard, &v_guard)));
+        drop((k_guard, v_guard, range));
+
+        let loaded = table
+            .get_model::<M>(model.as_key())
+            .expect("failed to get the model")
+            .expect("the model was not inserted");
+        assert_model("get_model", model, &loaded);
+
+        let loaded = table
+            .iter_models::<M>()
+            .expect("failed to iterate the table")
+            .next()
+            .expect("the model was not inserted")
+            .expect("failed to read the model");
+        assert_model("iter_models", model, &loaded);
+    }
+    txn.abort().expect("failed to abort the transaction");
+}
+
+/// Assert that the model returned by the given path equals the model.
+fn assert_model<M: PartialEq + Debug>(path: &str, model: &M, returned: &M) {
+    assert!(
+        model == returned,
+        "`{}` returned a different model\n    model: {:?}\n returned: {:?}",
+        path,
+        model,
+        returned
+    );
+}
+
+/// Assert that the given values of the model encode to the same bytes.
+fn assert_bytes<'a, V: Value>(
+    path: &str,
+    model: &impl Debug,
+    expected: V::SelfType<'a>,
+    returned: V::SelfType<'a>,
+) {
+    assert!(
+        V::as_bytes(&expected).as_ref() == V::as_bytes(&returned).as_ref(),
+        "`{}` returned a different value\n    model: {:?}",
+        path,
+        model
+    );
+}
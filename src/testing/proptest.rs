@@ -0,0 +1,32 @@
+//! Round-trip assertions of models generated by `proptest`.
+use std::fmt::Debug;
+
+use ::proptest::arbitrary::{any, Arbitrary};
+use ::proptest::test_runner::TestRunner;
+
+use crate::TableModel;
+
+/// Assert that models generated by the `proptest::arbitrary::Arbitrary`
+/// implementation of `M` round-trip, as asserted by [`super::assert_roundtrip`].
+///
+/// Cases are run by a `TestRunner` of the default configuration, such that the
+/// number of cases is set by the `PROPTEST_CASES` environment variable.
+///
+/// # Panics
+///
+/// Panics with the failed assertion of the simplest failing model, shrunk from
+/// the first failing model generated.
+pub fn assert_roundtrip<M>()
+where
+    M: TableModel + Arbitrary + PartialEq + Debug,
+{
+    let db = super::database();
+    let result = TestRunner::default().run(&any::<M>(), |model| {
+        super::assert_model_roundtrip(&db, &model);
+        Ok(())
+    });
+
+    if let Err(e) = result {
+        panic!("{}", e);
+    }
+}
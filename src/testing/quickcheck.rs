@@ -0,0 +1,37 @@
+//! Round-trip assertions of models generated by `quickcheck`.
+use std::fmt::Debug;
+
+use ::quickcheck::{Arbitrary, QuickCheck};
+use redb::Database;
+
+use crate::TableModel;
+
+thread_local! {
+    /// The database of the models asserted by the thread, as `quickcheck` tests a
+    /// function, rather than a closure.
+    static DATABASE: Database = super::database();
+}
+
+/// Assert that models generated by the `quickcheck::Arbitrary` implementation of
+/// `M` round-trip, as asserted by [`super::assert_roundtrip`].
+///
+/// Cases are run by a `QuickCheck` of the default configuration, such that the
+/// number of cases is set by the `QUICKCHECK_TESTS` environment variable.
+///
+/// # Panics
+///
+/// Panics with the failed assertion of the simplest failing model, shrunk from
+/// the first failing model generated.
+pub fn assert_roundtrip<M>()
+where
+    M: TableModel + Arbitrary + PartialEq + Debug,
+{
+    fn roundtrip<M>(model: M)
+    where
+        M: TableModel + PartialEq + Debug,
+    {
+        DATABASE.with(|db| super::assert_model_roundtrip(db, &model));
+    }
+
+    QuickCheck::new().quickcheck(roundtrip::<M> as fn(M));
+}
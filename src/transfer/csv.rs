@@ -0,0 +1,306 @@
+//! CSV export and import.
+use std::io::{BufRead, Write};
+
+use redb::{Database, ReadTransaction, WriteTransaction};
+
+use super::{Progress, BATCH_SIZE};
+use crate::{Error, Model, ReadableModelTable, TableModel};
+
+/// A CSV column of a model field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvField {
+    /// The field name.
+    pub name: &'static str,
+    /// Whether the field is of the key, rather than the value.
+    pub key: bool,
+}
+
+/// Conversion of a model to and from a CSV record, implemented by models
+/// declaring `csv`. Each field is converted with `ToString` and `FromStr`.
+pub trait CsvModel: Sized {
+    /// The fields of the key, followed by the fields of the value.
+    const FIELDS: &'static [CsvField];
+
+    /// Get the record of the model, in the order of `CsvModel::FIELDS`.
+    fn to_record(&self) -> Vec<String>;
+
+    /// Instantiate from a record, in the order of `CsvModel::FIELDS`.
+    fn from_record(record: &[&str]) -> Result<Self, String>;
+
+    /// Get the encoded `redb` key of a record of the key fields, in the order of
+    /// `CsvModel::FIELDS`.
+    fn key_bytes(record: &[&str]) -> Result<Vec<u8>, String>;
+}
+
+/// Options of a CSV export or import.
+///
+/// The header row holds the field names, optionally marked as `key:<name>` or
+/// `value:<name>`. Columns are imported by name, and may be in any order. Models
+/// are exported in key order, decoded with `ModelExt::from_guards`, and imported
+/// with `ModelExt::try_as_key_and_value`, calling hooks and validation as for any
+/// insertion, although timestamps are retained. Soft removed models are not
+/// exported.
+pub struct Csv<'p> {
+    delimiter: char,
+    markers: bool,
+    batch_size: usize,
+    progress: Progress<'p>,
+}
+
+impl<'p> Csv<'p> {
+    /// Create new `Csv` options, delimited by `,`, without markers, in batches of
+    /// 1000 models.
+    pub fn new() -> Self {
+        Self {
+            delimiter: ',',
+            markers: false,
+            batch_size: BATCH_SIZE,
+            progress: None,
+        }
+    }
+
+    /// Set the delimiter.
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Mark the header of each column as `key:<name>` or `value:<name>`.
+    pub fn markers(mut self, markers: bool) -> Self {
+        self.markers = markers;
+        self
+    }
+
+    /// Set the number of models per batch, reporting progress after each batch,
+    /// and committing after each batch of `Csv::import_batched`.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Set a callback, called with the number of models transferred after each
+    /// batch.
+    pub fn progress(mut self, progress: impl FnMut(u64) + 'p) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Export the table of `M` models, returning the number of models exported.
+    pub fn export<M: TableModel + CsvModel>(
+        &mut self,
+        txn: &ReadTransaction,
+        mut writer: impl Write,
+    ) -> Result<u64, Error> {
+        let table = txn.open_table(<M as Model>::DEFINITION)?;
+
+        let header = M::FIELDS
+            .iter()
+            .map(|field| match (self.markers, field.key) {
+                (false, _) => field.name.to_owned(),
+                (true, true) => format!("key:{}", field.name),
+                (true, false) => format!("value:{}", field.name),
+            });
+        write_record(&mut writer, header, self.delimiter)?;
+
+        let mut total = 0;
+        for model in table.iter_models::<M>()? {
+            write_record(&mut writer, model?.to_record(), self.delimiter)?;
+
+            total += 1;
+            if total % self.batch_size as u64 == 0 {
+                self.report(total);
+            }
+        }
+        writer.flush()?;
+
+        if total % self.batch_size as u64 != 0 {
+            self.report(total);
+        }
+        Ok(total)
+    }
+
+    /// Import `M` models within the given transaction, returning the number of
+    /// models imported.
+    pub fn import<M: TableModel + CsvModel>(
+        &mut self,
+        txn: &WriteTransaction,
+        reader: impl BufRead,
+    ) -> Result<u64, Error> {
+        let models = Records::new(reader, self.delimiter).models::<M>()?;
+        super::import_models(txn, models, self.batch_size, &mut self.progress)
+    }
+
+    /// Import `M` models, committing a write transaction per batch, and returning
+    /// the number of models imported. Batches committed before an error are
+    /// retained.
+    pub fn import_batched<M: TableModel + CsvModel>(
+        &mut self,
+        db: &Database,
+        reader: impl BufRead,
+    ) -> Result<u64, Error> {
+        let models = Records::new(reader, self.delimiter).models::<M>()?;
+        super::import_models_batched(db, models, self.batch_size, &mut self.progress)
+    }
+
+    /// Call the progress callback, if any.
+    fn report(&mut self, total: u64) {
+        if let Some(progress) = &mut self.progress {
+            progress(total);
+        }
+    }
+}
+
+impl Default for Csv<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Write a record, quoting fields containing the delimiter, quotes or line
+/// breaks. A record of a single empty field is quoted, such that it is not read
+/// as a blank line.
+fn write_record<S: AsRef<str>>(
+    writer: &mut impl Write,
+    record: impl IntoIterator<Item = S>,
+    delimiter: char,
+) -> Result<(), Error> {
+    let mut line = String::new();
+    for (i, field) in record.into_iter().enumerate() {
+        let field = field.as_ref();
+        if i > 0 {
+            line.push(delimiter);
+        }
+        if field.contains([delimiter, '"', '\n', '\r']) {
+            line.push('"');
+            line.push_str(&field.replace('"', "\"\""));
+            line.push('"');
+        } else {
+            line.push_str(field);
+        }
+    }
+    if line.is_empty() {
+        line.push_str("\"\"");
+    }
+    line.push('\n');
+
+    Ok(writer.write_all(line.as_bytes())?)
+}
+
+/// An iterator of CSV records, and the line number of each record. Blank lines
+/// are skipped.
+struct Records<R> {
+    reader: R,
+    delimiter: char,
+    line: u64,
+}
+
+impl<R: BufRead> Records<R> {
+    fn new(reader: R, delimiter: char) -> Self {
+        Self {
+            reader,
+            delimiter,
+            line: 0,
+        }
+    }
+
+    /// Read the header, mapping each field of `M` to a column, and get an
+    /// iterator of the models of the following records.
+    fn models<M: CsvModel>(mut self) -> Result<impl Iterator<Item = Result<M, Error>>, Error> {
+        let header = self.next().unwrap_or(Ok((1, Vec::new())))?.1;
+        let columns = M::FIELDS
+            .iter()
+            .map(|field| {
+                header
+                    .iter()
+                    .position(|column| {
+                        let (key, name) = match column.split_once(':') {
+                            Some(("key", name)) => (Some(true), name),
+                            Some(("value", name)) => (Some(false), name),
+                            _ => (None, column.as_str()),
+                        };
+                        name == field.name && key.unwrap_or(field.key) == field.key
+                    })
+                    .ok_or_else(|| Error::Csv {
+                        line: 1,
+                        message: format!("missing column `{}`", field.name),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let len = header.len();
+        Ok(self.map(move |record| {
+            let (line, record) = record?;
+            if record.len() != len {
+                return Err(Error::Csv {
+                    line,
+                    message: format!("expected {} fields, found {}", len, record.len()),
+                });
+            }
+            let record = columns
+                .iter()
+                .map(|i| record[*i].as_str())
+                .collect::<Vec<_>>();
+
+            M::from_record(&record).map_err(|message| Error::Csv { line, message })
+        }))
+    }
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = Result<(u64, Vec<String>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // Read lines until the quotes of the record are balanced.
+            let line = self.line + 1;
+            let mut buf = String::new();
+            loop {
+                match self.reader.read_line(&mut buf) {
+                    Ok(0) if buf.is_empty() => return None,
+                    Ok(0) => {
+                        return Some(Err(Error::Csv {
+                            line,
+                            message: String::from("unterminated quoted field"),
+                        }))
+                    }
+                    Ok(_) => self.line += 1,
+                    Err(e) => return Some(Err(e.into())),
+                }
+                if buf.matches('"').count().is_multiple_of(2) {
+                    break;
+                }
+            }
+            let record = buf.strip_suffix('\n').unwrap_or(&buf);
+            let record = record.strip_suffix('\r').unwrap_or(record);
+
+            // Skip blank lines, such as a trailing line.
+            if !record.is_empty() {
+                return Some(Ok((line, parse_record(record, self.delimiter))));
+            }
+        }
+    }
+}
+
+/// Parse the fields of a record, unquoting quoted fields.
+pub(crate) fn parse_record(record: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = record.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, c) if c == delimiter => fields.push(std::mem::take(&mut field)),
+            (false, c) => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    fields
+}
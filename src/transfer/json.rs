@@ -0,0 +1,183 @@
+//! JSON and JSON Lines export and import.
+use std::io::{BufRead, Write};
+
+use redb::{Database, ReadTransaction, WriteTransaction};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::{Progress, BATCH_SIZE};
+use crate::{Error, Model, ReadableModelTable, TableModel};
+
+/// Export the table of `M` models as JSON Lines, returning the number of models
+/// exported. See [`Json::export`].
+pub fn export<M: TableModel + Serialize>(
+    txn: &ReadTransaction,
+    writer: impl Write,
+) -> Result<u64, Error> {
+    Json::new().export::<M>(txn, writer)
+}
+
+/// Import `M` models from JSON Lines, returning the number of models imported.
+/// See [`Json::import`].
+pub fn import<M: TableModel + DeserializeOwned>(
+    txn: &WriteTransaction,
+    reader: impl BufRead,
+) -> Result<u64, Error> {
+    Json::new().import::<M>(txn, reader)
+}
+
+/// The format of exported models.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JsonFormat {
+    /// A model per line.
+    #[default]
+    Lines,
+    /// An array of models.
+    Array,
+}
+
+/// Options of a JSON export or import.
+///
+/// Models are exported in key order, decoded with `ModelExt::from_guards`, and
+/// imported with `ModelExt::try_as_key_and_value`, calling hooks and validation
+/// as for any insertion, although timestamps are retained. Soft removed models
+/// are not exported.
+pub struct Json<'p> {
+    format: JsonFormat,
+    batch_size: usize,
+    progress: Progress<'p>,
+}
+
+impl<'p> Json<'p> {
+    /// Create new `Json` options, exporting JSON Lines in batches of 1000 models.
+    pub fn new() -> Self {
+        Self {
+            format: JsonFormat::default(),
+            batch_size: BATCH_SIZE,
+            progress: None,
+        }
+    }
+
+    /// Set the format.
+    pub fn format(mut self, format: JsonFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Set the number of models per batch, reporting progress after each batch,
+    /// and committing after each batch of `Json::import_batched`.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Set a callback, called with the number of models transferred after each
+    /// batch.
+    pub fn progress(mut self, progress: impl FnMut(u64) + 'p) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Export the table of `M` models, returning the number of models exported.
+    pub fn export<M: TableModel + Serialize>(
+        &mut self,
+        txn: &ReadTransaction,
+        mut writer: impl Write,
+    ) -> Result<u64, Error> {
+        let table = txn.open_table(<M as Model>::DEFINITION)?;
+
+        let mut total = 0;
+        for model in table.iter_models::<M>()? {
+            match (self.format, total) {
+                (JsonFormat::Lines, _) => {}
+                (JsonFormat::Array, 0) => writer.write_all(b"[\n")?,
+                (JsonFormat::Array, _) => writer.write_all(b",\n")?,
+            }
+            serde_json::to_writer(&mut writer, &model?)?;
+            if self.format == JsonFormat::Lines {
+                writer.write_all(b"\n")?;
+            }
+
+            total += 1;
+            if total % self.batch_size as u64 == 0 {
+                self.report(total);
+            }
+        }
+        match (self.format, total) {
+            (JsonFormat::Lines, _) => {}
+            (JsonFormat::Array, 0) => writer.write_all(b"[]\n")?,
+            (JsonFormat::Array, _) => writer.write_all(b"\n]\n")?,
+        }
+        writer.flush()?;
+
+        if total % self.batch_size as u64 != 0 {
+            self.report(total);
+        }
+        Ok(total)
+    }
+
+    /// Import `M` models within the given transaction, returning the number of
+    /// models imported.
+    pub fn import<M: TableModel + DeserializeOwned>(
+        &mut self,
+        txn: &WriteTransaction,
+        reader: impl BufRead,
+    ) -> Result<u64, Error> {
+        let (batch_size, progress) = (self.batch_size, &mut self.progress);
+        match self.format {
+            JsonFormat::Lines => {
+                super::import_models(txn, lines::<M>(reader), batch_size, progress)
+            }
+            JsonFormat::Array => {
+                super::import_models(txn, array::<M>(reader)?, batch_size, progress)
+            }
+        }
+    }
+
+    /// Import `M` models, committing a write transaction per batch, and returning
+    /// the number of models imported. Batches committed before an error are
+    /// retained.
+    pub fn import_batched<M: TableModel + DeserializeOwned>(
+        &mut self,
+        db: &Database,
+        reader: impl BufRead,
+    ) -> Result<u64, Error> {
+        let (batch_size, progress) = (self.batch_size, &mut self.progress);
+        match self.format {
+            JsonFormat::Lines => {
+                super::import_models_batched(db, lines::<M>(reader), batch_size, progress)
+            }
+            JsonFormat::Array => {
+                super::import_models_batched(db, array::<M>(reader)?, batch_size, progress)
+            }
+        }
+    }
+
+    /// Call the progress callback, if any.
+    fn report(&mut self, total: u64) {
+        if let Some(progress) = &mut self.progress {
+            progress(total);
+        }
+    }
+}
+
+impl Default for Json<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read models from JSON Lines as they are consumed.
+fn lines<M: DeserializeOwned>(reader: impl BufRead) -> impl Iterator<Item = Result<M, Error>> {
+    serde_json::Deserializer::from_reader(reader)
+        .into_iter::<M>()
+        .map(|model| Ok(model?))
+}
+
+/// Read models from a JSON array.
+fn array<M: DeserializeOwned>(
+    reader: impl BufRead,
+) -> Result<impl Iterator<Item = Result<M, Error>>, Error> {
+    let models: Vec<M> = serde_json::from_reader(reader)?;
+    Ok(models.into_iter().map(Ok))
+}
@@ -0,0 +1,85 @@
+//! Export and import of model tables.
+use redb::{Database, WriteTransaction};
+
+use crate::{history, table, Error, Model, TableModel};
+
+mod csv;
+#[cfg(feature = "serde")]
+mod json;
+
+pub(crate) use self::csv::parse_record;
+pub use self::csv::{Csv, CsvField, CsvModel};
+#[cfg(feature = "serde")]
+pub use self::json::{export, import, Json, JsonFormat};
+
+/// The default number of models per batch.
+const BATCH_SIZE: usize = 1000;
+
+/// A callback, called with the number of models transferred after each batch.
+type Progress<'p> = Option<Box<dyn FnMut(u64) + 'p>>;
+
+/// Insert up to `limit` models, returning the number of models inserted.
+/// Timestamps of the models are retained, while `ModelHooks::before_insert`,
+/// validation and the history of models declaring `history` apply as for any
+/// insertion.
+fn insert_models<M: TableModel>(
+    txn: &WriteTransaction,
+    models: &mut impl Iterator<Item = Result<M, Error>>,
+    limit: usize,
+) -> Result<usize, Error> {
+    let mut table = txn.open_table(<M as Model>::DEFINITION)?;
+    let mut inserted = 0;
+    for model in models.take(limit) {
+        let mut model = model?;
+        let previous = table::insert(&mut table, &mut model)?;
+        history::record(txn, &model, previous.as_ref())?;
+        inserted += 1;
+    }
+
+    Ok(inserted)
+}
+
+/// Insert the models in the given transaction, reporting progress after each
+/// batch.
+fn import_models<M: TableModel>(
+    txn: &WriteTransaction,
+    mut models: impl Iterator<Item = Result<M, Error>>,
+    batch_size: usize,
+    progress: &mut Progress,
+) -> Result<u64, Error> {
+    let mut total = 0;
+    loop {
+        let inserted = insert_models(txn, &mut models, batch_size)?;
+        total += inserted as u64;
+        if let Some(progress) = progress {
+            progress(total);
+        }
+        if inserted < batch_size {
+            return Ok(total);
+        }
+    }
+}
+
+/// Insert the models, committing a write transaction per batch and reporting
+/// progress after each commit. Batches committed before an error are retained.
+fn import_models_batched<M: TableModel>(
+    db: &Database,
+    mut models: impl Iterator<Item = Result<M, Error>>,
+    batch_size: usize,
+    progress: &mut Progress,
+) -> Result<u64, Error> {
+    let mut total = 0;
+    loop {
+        let txn = db.begin_write()?;
+        let inserted = insert_models(&txn, &mut models, batch_size)?;
+        txn.commit()?;
+
+        total += inserted as u64;
+        if let Some(progress) = progress {
+            progress(total);
+        }
+        if inserted < batch_size {
+            return Ok(total);
+        }
+    }
+}
@@ -0,0 +1,392 @@
+//! Model operations on `redb` transactions.
+use std::any::{Any, TypeId};
+use std::borrow::Borrow;
+use std::cell::{RefCell, RefMut};
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::ops::{Deref, RangeBounds};
+use std::rc::Rc;
+
+use redb::{ReadOnlyTable, Table, Value};
+
+use crate::notify::{Change, Subscribers};
+use crate::table::{self, Unrecorded};
+use crate::{history, BatchCount, SystemClock};
+use crate::{Error, Model, ModelPatch, ModelRange, ModelTable, ReadableModelTable, TableModel};
+
+/// The read only table of `M` models.
+type ReadOnlyModelTable<M> = ReadOnlyTable<<M as TableModel>::Key, <M as TableModel>::Value>;
+
+/// The table of `M` models, borrowing the transaction of a `WriteTxn`.
+type WriteModelTable<M> = Table<'static, <M as TableModel>::Key, <M as TableModel>::Value>;
+
+/// A recorded change, publishing it to the subscribers.
+type Publish = Box<dyn FnOnce(&Subscribers)>;
+
+/// A `redb` write transaction, recording the changes of model operations and
+/// publishing them to the [`Subscribers`] of the database once committed, and
+/// appending the previous
+/// models to the history of models declaring `history`. Dereferences to the
+/// `redb::WriteTransaction`, although changes made directly are not recorded.
+///
+/// The table of each model is opened once and cached until the transaction is
+/// committed or aborted, such that the table of a model operated on may not be
+/// opened directly from the `redb::WriteTransaction`.
+pub struct WriteTxn {
+    // Declared before the transaction borrowed by the tables, dropping them first.
+    tables: RefCell<BTreeMap<TypeId, Box<dyn Any>>>,
+    changes: RefCell<Vec<Publish>>,
+    subscribers: Subscribers,
+    // Held by an `Rc` for a stable address, shared with the tables borrowing it.
+    txn: Rc<redb::WriteTransaction>,
+}
+
+impl WriteTxn {
+    /// Create a new `WriteTxn` from a `redb` write transaction, without any
+    /// subscribers to the changes.
+    pub fn new(txn: redb::WriteTransaction) -> Self {
+        Self::with_subscribers(txn, &Subscribers::new())
+    }
+
+    /// Create a new `WriteTxn` from a `redb` write transaction, publishing the
+    /// changes to the subscribers.
+    pub fn with_subscribers(txn: redb::WriteTransaction, subscribers: &Subscribers) -> Self {
+        Self {
+            tables: RefCell::new(BTreeMap::new()),
+            changes: RefCell::new(Vec::new()),
+            subscribers: subscribers.clone(),
+            txn: Rc::new(txn),
+        }
+    }
+
+    /// Get the model of the given key. See `ReadableModelTable::get_model`.
+    pub fn get<'k, M: TableModel>(
+        &self,
+        key: impl Borrow<<M::Key as Value>::SelfType<'k>>,
+    ) -> Result<Option<M>, Error> {
+        self.table::<M>()?.get_model(key)
+    }
+
+    /// Get the models within the given key range. See
+    /// `ReadableModelTable::range_models`.
+    pub fn range<'a, M, KR>(&self, range: impl RangeBounds<KR> + 'a) -> Result<Vec<M>, Error>
+    where
+        M: TableModel,
+        KR: Borrow<<M::Key as Value>::SelfType<'a>> + 'a,
+    {
+        let table = self.table::<M>()?;
+        let models = table.range_models::<M, KR>(range)?.collect();
+        models
+    }
+
+    /// Insert the model, returning the replaced model, if any. See
+    /// `ModelTable::insert_model`.
+    pub fn insert<M: TableModel + Clone + Send>(&self, model: &mut M) -> Result<Option<M>, Error> {
+        let mut table = self.table::<M>()?;
+        let previous = Unrecorded(&mut table).insert_model(model)?;
+
+        self.inserted(model, previous.clone())?;
+        Ok(previous)
+    }
+
+    /// Replace an existing model, returning the replaced model. See
+    /// `ModelTable::update_model`.
+    pub fn update<M: TableModel + Clone + Send>(&self, model: &mut M) -> Result<M, Error> {
+        let mut table = self.table::<M>()?;
+        let previous = Unrecorded(&mut table).update_model(model)?;
+
+        self.inserted(model, Some(previous.clone()))?;
+        Ok(previous)
+    }
+
+    /// Apply the patch to the model of the given key, returning the patched model.
+    /// See `ModelTable::patch_model`.
+    pub fn patch<'k, M: TableModel + ModelPatch + Clone + Send>(
+        &self,
+        key: impl Borrow<<M::Key as Value>::SelfType<'k>>,
+        patch: M::Patch,
+    ) -> Result<M, Error> {
+        let mut table = self.table::<M>()?;
+        let mut model = table.get_model::<M>(key)?.ok_or(Error::NotFound)?;
+        model.apply_patch(patch);
+        let previous = Unrecorded(&mut table).update_model(&mut model)?;
+
+        self.inserted(&model, Some(previous))?;
+        Ok(model)
+    }
+
+    /// Insert the model as given, returning the replaced model, if any. See
+    /// `ModelTable::replace_model`.
+    pub fn replace<M: TableModel + Clone + Send>(&self, model: &M) -> Result<Option<M>, Error> {
+        let mut table = self.table::<M>()?;
+        let previous = Unrecorded(&mut table).replace_model(model)?;
+
+        self.inserted(model, previous.clone())?;
+        Ok(previous)
+    }
+
+    /// Insert the models, returning the number of models inserted and replaced.
+    /// See `ModelTable::insert_many`.
+    pub fn insert_many<M: TableModel + Clone + Send>(
+        &self,
+        models: impl IntoIterator<Item = M>,
+        sort: bool,
+    ) -> Result<BatchCount, Error> {
+        let mut table = self.table::<M>()?;
+        table::insert_many(&mut table, models, sort, &SystemClock, |model, guard| {
+            let previous = guard.map(|guard| M::from_key_and_guard((model.as_key(), &guard)));
+            self.inserted(model, previous)
+        })
+    }
+
+    /// Insert or replace the models, returning the number of models inserted and
+    /// replaced. See `ModelTable::upsert_many`.
+    pub fn upsert_many<M: TableModel + Clone + Send>(
+        &self,
+        models: impl IntoIterator<Item = M>,
+        sort: bool,
+    ) -> Result<BatchCount, Error> {
+        let mut table = self.table::<M>()?;
+        table::upsert_many(&mut table, models, sort, &SystemClock, |model, guard| {
+            let previous = guard.map(|guard| M::from_key_and_guard((model.as_key(), &guard)));
+            self.inserted(model, previous)
+        })
+    }
+
+    /// Remove the model of the given key, returning the removed model, if any.
+    /// See `ModelTable::remove_model`.
+    pub fn remove<'k, M: TableModel + Clone + Send>(
+        &self,
+        key: impl Borrow<<M::Key as Value>::SelfType<'k>>,
+    ) -> Result<Option<M>, Error> {
+        let mut table = self.table::<M>()?;
+        let removed = Unrecorded(&mut table).remove_model::<M>(key)?;
+
+        self.removed(removed.as_ref())?;
+        Ok(removed)
+    }
+
+    /// Remove the model of the given key without calling hooks, returning the
+    /// removed model, if any. See `ModelTable::take_model`.
+    pub fn take<'k, M: TableModel + Clone + Send>(
+        &self,
+        key: impl Borrow<<M::Key as Value>::SelfType<'k>>,
+    ) -> Result<Option<M>, Error> {
+        let mut table = self.table::<M>()?;
+        let removed = Unrecorded(&mut table).take_model::<M>(key)?;
+
+        self.removed(removed.as_ref())?;
+        Ok(removed)
+    }
+
+    /// Mark the model of the given key as removed, returning the removed model,
+    /// if any. See `ModelTable::soft_remove_model`.
+    pub fn soft_remove<'k, M: TableModel + Clone + Send>(
+        &self,
+        key: impl Borrow<<M::Key as Value>::SelfType<'k>>,
+    ) -> Result<Option<M>, Error> {
+        let mut table = self.table::<M>()?;
+        let removed = Unrecorded(&mut table).soft_remove_model::<M>(key)?;
+
+        self.removed(removed.as_ref())?;
+        Ok(removed)
+    }
+
+    /// Restore the soft removed model of the given key, returning the restored
+    /// model, if any. See `ModelTable::restore_model`.
+    pub fn restore<'k, M: TableModel + Clone + Send>(
+        &self,
+        key: impl Borrow<<M::Key as Value>::SelfType<'k>>,
+    ) -> Result<Option<M>, Error> {
+        let mut table = self.table::<M>()?;
+        let restored = Unrecorded(&mut table).restore_model::<M>(key)?;
+
+        if let Some(restored) = &restored {
+            self.inserted(restored, None)?;
+        }
+        Ok(restored)
+    }
+
+    /// Remove all soft removed models, returning the number of models removed.
+    /// Removals are recorded as models are soft removed, rather than purged. See
+    /// `ModelTable::purge_models`.
+    pub fn purge<M: TableModel>(&self) -> Result<u64, Error> {
+        self.table::<M>()?.purge_models::<M>()
+    }
+
+    /// Commit the transaction, publishing the recorded changes.
+    pub fn commit(self) -> Result<(), Error> {
+        let subscribers = self.subscribers.clone();
+        let (txn, changes) = self.into_parts();
+        txn.commit()?;
+        for publish in changes {
+            publish(&subscribers);
+        }
+
+        Ok(())
+    }
+
+    /// Abort the transaction, discarding the recorded changes.
+    pub fn abort(self) -> Result<(), Error> {
+        Ok(self.into_parts().0.abort()?)
+    }
+
+    /// Close the cached tables, returning the transaction and recorded changes.
+    fn into_parts(self) -> (redb::WriteTransaction, Vec<Publish>) {
+        let Self {
+            tables,
+            changes,
+            txn,
+            ..
+        } = self;
+        drop(tables);
+        let txn = Rc::into_inner(txn).expect("transaction borrowed by a closed table");
+
+        (txn, changes.into_inner())
+    }
+
+    /// Get the table of `M`, opening it if not yet cached.
+    pub(crate) fn table<M: TableModel>(&self) -> Result<RefMut<'_, WriteModelTable<M>>, Error> {
+        let mut tables = self.tables.borrow_mut();
+        if let Entry::Vacant(entry) = tables.entry(TypeId::of::<M>()) {
+            // SAFETY: The transaction is never moved, being held by the `Rc`, and
+            // outlives the cached tables, which are dropped first. Tables are only
+            // borrowed for the lifetime of `self`, and the transaction is only
+            // borrowed shared, as by `redb::WriteTransaction::open_table`.
+            let txn: &'static redb::WriteTransaction = unsafe { &*Rc::as_ptr(&self.txn) };
+            entry.insert(Box::new(txn.open_table(<M as Model>::DEFINITION)?));
+        }
+
+        Ok(RefMut::map(tables, |tables| {
+            tables
+                .get_mut(&TypeId::of::<M>())
+                .and_then(|table| table.downcast_mut::<WriteModelTable<M>>())
+                .expect("cached table of another model")
+        }))
+    }
+
+    /// Record the history and change of a model inserted, replacing the previous
+    /// model, if any.
+    pub(crate) fn inserted<M: TableModel + Clone + Send>(
+        &self,
+        model: &M,
+        previous: Option<M>,
+    ) -> Result<(), Error> {
+        history::record(&self.txn, model, previous.as_ref())?;
+        self.record(match previous {
+            Some(previous) => Change::Update {
+                previous,
+                model: model.clone(),
+            },
+            None => Change::Insert(model.clone()),
+        });
+
+        Ok(())
+    }
+
+    /// Record the history and change of a removed model, if any.
+    fn removed<M: TableModel + Clone + Send>(&self, removed: Option<&M>) -> Result<(), Error> {
+        if let Some(removed) = removed {
+            history::record(&self.txn, removed, Some(removed))?;
+            self.record(Change::Remove(removed.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Record a change, published once the transaction is committed.
+    fn record<M: Clone + Send + 'static>(&self, change: Change<M>) {
+        self.changes
+            .borrow_mut()
+            .push(Box::new(move |subscribers| subscribers.publish(change)));
+    }
+}
+
+impl From<redb::WriteTransaction> for WriteTxn {
+    fn from(txn: redb::WriteTransaction) -> Self {
+        Self::new(txn)
+    }
+}
+
+impl Deref for WriteTxn {
+    type Target = redb::WriteTransaction;
+
+    fn deref(&self) -> &Self::Target {
+        &self.txn
+    }
+}
+
+/// A `redb` read transaction, opening the table of each model once and caching
+/// it for the lifetime of the transaction. Dereferences to the
+/// `redb::ReadTransaction`.
+pub struct ReadTxn {
+    txn: redb::ReadTransaction,
+    tables: RefCell<BTreeMap<TypeId, Rc<dyn Any>>>,
+}
+
+impl ReadTxn {
+    /// Create a new `ReadTxn` from a `redb` read transaction.
+    pub fn new(txn: redb::ReadTransaction) -> Self {
+        Self {
+            txn,
+            tables: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    /// Get the model of the given key. See `ReadableModelTable::get_model`.
+    pub fn get<'k, M: TableModel>(
+        &self,
+        key: impl Borrow<<M::Key as Value>::SelfType<'k>>,
+    ) -> Result<Option<M>, Error> {
+        self.table::<M>()?.get_model(key)
+    }
+
+    /// Get an iterator of the models within the given key range. See
+    /// `ReadableModelTable::range_models`.
+    pub fn range<'a, M, KR>(
+        &self,
+        range: impl RangeBounds<KR> + 'a,
+    ) -> Result<ModelRange<'static, M::Key, M::Value, M>, Error>
+    where
+        M: TableModel,
+        KR: Borrow<<M::Key as Value>::SelfType<'a>> + 'a,
+    {
+        Ok(ModelRange::new(self.table::<M>()?.range(range)?, false))
+    }
+
+    /// Get an iterator of all models in the table.
+    pub fn iter<M: TableModel>(&self) -> Result<ModelRange<'static, M::Key, M::Value, M>, Error> {
+        self.range::<M, <M::Key as Value>::SelfType<'_>>(..)
+    }
+
+    /// Get the table of `M`, opening it if not yet cached.
+    fn table<M: TableModel>(&self) -> Result<Rc<ReadOnlyModelTable<M>>, Error> {
+        let mut tables = self.tables.borrow_mut();
+        let table = match tables.get(&TypeId::of::<M>()) {
+            Some(table) => table.clone(),
+            None => {
+                let table: Rc<dyn Any> = Rc::new(self.txn.open_table(<M as Model>::DEFINITION)?);
+                tables.insert(TypeId::of::<M>(), table.clone());
+                table
+            }
+        };
+
+        Ok(table
+            .downcast::<ReadOnlyModelTable<M>>()
+            .expect("cached table of another model"))
+    }
+}
+
+impl From<redb::ReadTransaction> for ReadTxn {
+    fn from(txn: redb::ReadTransaction) -> Self {
+        Self::new(txn)
+    }
+}
+
+impl Deref for ReadTxn {
+    type Target = redb::ReadTransaction;
+
+    fn deref(&self) -> &Self::Target {
+        &self.txn
+    }
+}
@@ -0,0 +1,289 @@
+//! Adapter tests.
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use redb::{backends::InMemoryBackend, Database};
+use redb_model::{Model, ModelExt};
+
+/// Insert the model, and assert that the entry loaded by key is equal.
+macro_rules! test_roundtrip {
+    (
+        $database:ident,
+        $struct_name:ident,
+        $model:ident
+    ) => {
+        let txn = $database.begin_write().unwrap();
+        {
+            let mut table = txn.open_table($struct_name::DEFINITION).unwrap();
+            let (k, v) = $model.as_key_and_value();
+            table.insert(k, v).unwrap();
+        }
+        txn.commit().unwrap();
+
+        let txn = $database.begin_read().unwrap();
+        let table = txn.open_table($struct_name::DEFINITION).unwrap();
+        let k = $model.as_key();
+        let model = table
+            .get(k)
+            .unwrap()
+            .map(|guard| $struct_name::from_key_and_guard((k, &guard)))
+            .unwrap();
+
+        assert_eq!($model, model);
+    };
+}
+
+#[test]
+fn test_net_adapter() {
+    let db = Database::builder()
+        .create_with_backend(InMemoryBackend::new())
+        .unwrap();
+
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext)]
+    struct TestModel {
+        #[entry(position = "key", adapter = "net")]
+        ip: IpAddr,
+        #[entry(position = "value", adapter = "net")]
+        ipv4: Ipv4Addr,
+        #[entry(position = "value", adapter = "net")]
+        ipv6: Ipv6Addr,
+        #[entry(position = "value", adapter = "net")]
+        socket: SocketAddr,
+    }
+
+    let model = TestModel {
+        ip: IpAddr::V6(Ipv6Addr::LOCALHOST),
+        ipv4: Ipv4Addr::new(192, 168, 0, 1),
+        ipv6: Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1),
+        socket: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8080),
+    };
+
+    test_roundtrip!(db, TestModel, model);
+}
+
+#[test]
+#[should_panic(expected = "IP version 5 is neither 4 nor 6")]
+fn test_net_adapter_version() {
+    use redb_model::adapter::{Adapter, Net};
+
+    let _: IpAddr = Net::from_redb((5, 1));
+}
+
+#[test]
+fn test_std_time_adapter() {
+    let db = Database::builder()
+        .create_with_backend(InMemoryBackend::new())
+        .unwrap();
+
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext)]
+    struct TestModel {
+        #[entry(position = "key", adapter = "std_time")]
+        time: SystemTime,
+        #[entry(position = "value", adapter = "std_time")]
+        before_epoch: SystemTime,
+        #[entry(position = "value", adapter = "std_time")]
+        duration: Duration,
+    }
+
+    let model = TestModel {
+        time: UNIX_EPOCH + Duration::new(1_700_000_000, 123),
+        before_epoch: UNIX_EPOCH - Duration::new(10, 250),
+        duration: Duration::new(5, 10),
+    };
+
+    test_roundtrip!(db, TestModel, model);
+}
+
+#[test]
+fn test_std_path_adapter() {
+    let db = Database::builder()
+        .create_with_backend(InMemoryBackend::new())
+        .unwrap();
+
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext)]
+    struct TestModel {
+        #[entry(position = "key", adapter = "std_path")]
+        path: PathBuf,
+    }
+
+    let model = TestModel {
+        path: PathBuf::from("/tmp/redb_model"),
+    };
+    test_roundtrip!(db, TestModel, model);
+
+    // Paths that are not valid UTF-8 are retained.
+    #[cfg(unix)]
+    {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let model = TestModel {
+            path: PathBuf::from(OsStr::from_bytes(&[0xff, 0xfe])),
+        };
+        test_roundtrip!(db, TestModel, model);
+    }
+}
+
+#[test]
+fn test_custom_adapter() {
+    struct Celsius;
+
+    impl redb_model::adapter::Adapter<f64> for Celsius {
+        type RedbType = i64;
+
+        fn from_redb(value: i64) -> f64 {
+            value as f64 / 100.0
+        }
+
+        fn into_redb(value: &f64) -> i64 {
+            (value * 100.0).round() as i64
+        }
+    }
+
+    let db = Database::builder()
+        .create_with_backend(InMemoryBackend::new())
+        .unwrap();
+
+    #[derive(Model, PartialEq, Debug)]
+    #[model(impl_ext)]
+    struct TestModel {
+        #[entry(position = "key")]
+        id: u32,
+        #[entry(position = "value", adapter = "Celsius")]
+        temperature: f64,
+    }
+
+    let model = TestModel {
+        id: 0,
+        temperature: 21.5,
+    };
+
+    test_roundtrip!(db, TestModel, model);
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn test_uuid_adapter() {
+    let db = Database::builder()
+        .create_with_backend(InMemoryBackend::new())
+        .unwrap();
+
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext)]
+    struct TestModel {
+        #[entry(position = "key", adapter = "uuid")]
+        id: uuid::Uuid,
+    }
+
+    let model = TestModel {
+        id: uuid::Uuid::from_u128(0x1234),
+    };
+
+    test_roundtrip!(db, TestModel, model);
+}
+
+#[cfg(feature = "ulid")]
+#[test]
+fn test_ulid_adapter() {
+    let db = Database::builder()
+        .create_with_backend(InMemoryBackend::new())
+        .unwrap();
+
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext)]
+    struct TestModel {
+        #[entry(position = "key", adapter = "ulid")]
+        id: ulid::Ulid,
+    }
+
+    let model = TestModel {
+        id: ulid::Ulid::from_parts(1_700_000_000_000, 42),
+    };
+
+    test_roundtrip!(db, TestModel, model);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_chrono_adapter() {
+    use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+
+    let db = Database::builder()
+        .create_with_backend(InMemoryBackend::new())
+        .unwrap();
+
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext)]
+    struct TestModel {
+        #[entry(position = "key", adapter = "chrono")]
+        created: DateTime<Utc>,
+        #[entry(position = "value", adapter = "chrono")]
+        date: NaiveDate,
+        #[entry(position = "value", adapter = "chrono")]
+        time: NaiveTime,
+    }
+
+    let model = TestModel {
+        created: DateTime::from_timestamp(1_700_000_000, 123).unwrap(),
+        date: NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+        time: NaiveTime::from_hms_nano_opt(23, 59, 59, 999).unwrap(),
+    };
+
+    test_roundtrip!(db, TestModel, model);
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_time_adapter() {
+    use time::{Date, Month, OffsetDateTime};
+
+    let db = Database::builder()
+        .create_with_backend(InMemoryBackend::new())
+        .unwrap();
+
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext)]
+    struct TestModel {
+        #[entry(position = "key", adapter = "time")]
+        created: OffsetDateTime,
+        #[entry(position = "value", adapter = "time")]
+        date: Date,
+    }
+
+    let model = TestModel {
+        created: OffsetDateTime::from_unix_timestamp_nanos(1_700_000_000_000_000_123).unwrap(),
+        date: Date::from_calendar_date(2024, Month::February, 29).unwrap(),
+    };
+
+    test_roundtrip!(db, TestModel, model);
+}
+
+#[cfg(feature = "rust_decimal")]
+#[test]
+fn test_rust_decimal_adapter() {
+    use rust_decimal::Decimal;
+
+    let db = Database::builder()
+        .create_with_backend(InMemoryBackend::new())
+        .unwrap();
+
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext)]
+    struct TestModel {
+        #[entry(position = "key")]
+        id: u32,
+        #[entry(position = "value", adapter = "rust_decimal")]
+        price: Decimal,
+    }
+
+    let model = TestModel {
+        id: 0,
+        price: Decimal::new(1999, 2),
+    };
+
+    test_roundtrip!(db, TestModel, model);
+}
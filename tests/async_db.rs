@@ -0,0 +1,138 @@
+//! Async database tests.
+#![cfg(feature = "async")]
+mod common;
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::{mpsc, Arc};
+use std::task::{Context, Poll, Wake};
+use std::thread::{self, Thread};
+
+use redb::ReadableTableMetadata;
+use redb_model::{AsyncModelDb, Change, Model, ModelTable};
+
+#[derive(Model, PartialEq, Eq, Debug, Clone)]
+#[model(impl_ext)]
+struct User {
+    #[entry(position = "key", redb_type = "&str")]
+    name: String,
+    #[entry(position = "value")]
+    age: u32,
+}
+
+fn user(name: &str, age: u32) -> User {
+    User {
+        name: String::from(name),
+        age,
+    }
+}
+
+/// Block the current thread on the future, unparking when woken.
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Arc::new(Unpark(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[test]
+fn test_async_model_db() {
+    let db = common::database();
+    let db = AsyncModelDb::with_threads(db, 2);
+
+    block_on(async {
+        assert_eq!(db.insert(user("a", 1)).await.unwrap(), None);
+        assert_eq!(db.insert(user("b", 2)).await.unwrap(), None);
+        assert_eq!(db.update(user("b", 3)).await.unwrap(), user("b", 2));
+
+        let name = String::from("a");
+        assert_eq!(
+            db.get::<User>(name.as_str()).await.unwrap(),
+            Some(user("a", 1))
+        );
+        assert_eq!(db.get::<User>("c").await.unwrap(), None);
+        assert_eq!(
+            db.range::<User, &str>("a".."c").await.unwrap(),
+            [user("a", 1), user("b", 3)]
+        );
+
+        let inserted = db
+            .write(|txn| {
+                let mut table = txn.open_table(User::DEFINITION)?;
+                table.insert_model(&mut user("c", 4))?;
+                Ok(table.len()?)
+            })
+            .await
+            .unwrap();
+        assert_eq!(inserted, 3);
+
+        assert_eq!(db.remove::<User>("a").await.unwrap(), Some(user("a", 1)));
+        assert_eq!(db.range::<User, &str>(..).await.unwrap().len(), 2);
+    });
+}
+
+#[test]
+fn test_async_subscribe() {
+    #[derive(Model, PartialEq, Eq, Debug, Clone)]
+    #[model(impl_ext)]
+    struct Post {
+        #[entry(position = "key")]
+        id: u32,
+        #[entry(position = "value")]
+        likes: u32,
+    }
+
+    let db = common::database();
+    let db = AsyncModelDb::with_threads(db, 1);
+    let receiver = db.subscribe::<Post>();
+
+    block_on(async {
+        db.insert(Post { id: 0, likes: 0 }).await.unwrap();
+        db.update(Post { id: 0, likes: 1 }).await.unwrap();
+        db.remove::<Post>(0).await.unwrap();
+    });
+
+    assert_eq!(
+        receiver.try_iter().collect::<Vec<_>>(),
+        [
+            Change::Insert(Post { id: 0, likes: 0 }),
+            Change::Update {
+                previous: Post { id: 0, likes: 0 },
+                model: Post { id: 0, likes: 1 },
+            },
+            Change::Remove(Post { id: 0, likes: 1 }),
+        ]
+    );
+}
+
+#[test]
+fn test_async_read_during_write() {
+    let db = common::database();
+    let db = AsyncModelDb::with_threads(db, 1);
+    block_on(db.insert(user("a", 1))).unwrap();
+
+    // The write holds its thread until released, while reads run on their own.
+    let (release, released) = mpsc::channel::<()>();
+    let write = db.write(move |txn| {
+        released.recv().unwrap();
+        txn.insert(&mut user("b", 2))
+    });
+    assert_eq!(block_on(db.get::<User>("a")).unwrap(), Some(user("a", 1)));
+
+    release.send(()).unwrap();
+    assert_eq!(block_on(write).unwrap(), None);
+    assert_eq!(block_on(db.get::<User>("b")).unwrap(), Some(user("b", 2)));
+}
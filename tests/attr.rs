@@ -1,6 +1,6 @@
 //! Attribute tests.
 use redb::{MultimapTableHandle, TableHandle};
-use redb_model::{Model, ModelExt};
+use redb_model::{FieldChange, Model, ModelDiff, ModelExt, ValidationError};
 
 #[cfg(test)]
 mod tests {
@@ -60,4 +60,125 @@ mod tests {
         assert_eq!(10 as <TestModel as ModelExt>::RedbKey, 10u8);
         assert_eq!(10 as <TestModel as ModelExt>::RedbValue, 10u32);
     }
+
+    #[test]
+    fn test_entry_order() {
+        #[derive(Model, Debug, PartialEq)]
+        #[model(impl_ext)]
+        struct TestModel {
+            #[entry(position = "value", order = 1)]
+            v1: u64,
+            #[entry(position = "key", order = 1)]
+            k1: u8,
+            #[entry(position = "value", order = 0)]
+            v0: u32,
+            #[entry(position = "key", order = 0)]
+            k0: u16,
+        }
+
+        let model = TestModel {
+            v1: 3,
+            k1: 1,
+            v0: 2,
+            k0: 0,
+        };
+
+        assert_eq!(model.as_key(), (0u16, 1u8));
+        assert_eq!(model.as_value(), (2u32, 3u64));
+        assert_eq!(TestModel::from_values(((0, 1), (2, 3))), model);
+    }
+
+    #[test]
+    fn test_validate() {
+        fn non_empty(value: &str) -> Result<(), &'static str> {
+            match value.is_empty() {
+                true => Err("must not be empty"),
+                false => Ok(()),
+            }
+        }
+
+        fn ordered(model: &TestModel) -> Result<(), String> {
+            match model.min <= model.max {
+                true => Ok(()),
+                false => Err(format!("{} exceeds {}", model.min, model.max)),
+            }
+        }
+
+        #[derive(Model, Debug)]
+        #[model(impl_ext, validate = "ordered")]
+        struct TestModel {
+            #[entry(position = "key", redb_type = "&str", validate = "non_empty")]
+            name: String,
+            #[entry(position = "value")]
+            min: u32,
+            #[entry(position = "value")]
+            max: u32,
+        }
+
+        let valid = TestModel {
+            name: String::from("name"),
+            min: 0,
+            max: 1,
+        };
+        assert!(valid.validate().is_ok());
+        assert_eq!(valid.try_as_key_and_value(), Ok(("name", (0, 1))));
+
+        let invalid_field = TestModel {
+            name: String::new(),
+            min: 0,
+            max: 1,
+        };
+        let e = invalid_field.try_as_key_and_value().unwrap_err();
+        assert_eq!(e, ValidationError::field("name", "must not be empty"));
+        assert_eq!(e.to_string(), "invalid field `name`: must not be empty");
+
+        let invalid_model = TestModel {
+            name: String::from("name"),
+            min: 2,
+            max: 1,
+        };
+        let e = invalid_model.validate().unwrap_err();
+        assert_eq!(e.field_name(), None);
+        assert_eq!(e.to_string(), "invalid model: 2 exceeds 1");
+    }
+
+    #[test]
+    fn test_diff() {
+        #[derive(Model, Clone)]
+        #[model(diff)]
+        struct TestModel {
+            #[entry(position = "key")]
+            id: u32,
+            #[entry(position = "value", order = 1)]
+            tags: Vec<String>,
+            #[entry(position = "value", order = 0)]
+            name: String,
+        }
+
+        let before = TestModel {
+            id: 0,
+            tags: vec![String::from("a")],
+            name: String::from("name"),
+        };
+        assert!(before.diff(&before.clone()).is_empty());
+
+        let mut after = before.clone();
+        after.tags.push(String::from("b"));
+        after.name = String::from("renamed");
+        assert_eq!(
+            before.diff(&after),
+            [
+                FieldChange {
+                    field: "name",
+                    before: String::from("\"name\""),
+                    after: String::from("\"renamed\""),
+                },
+                FieldChange {
+                    field: "tags",
+                    before: String::from("[\"a\"]"),
+                    after: String::from("[\"a\", \"b\"]"),
+                },
+            ]
+        );
+    }
 }
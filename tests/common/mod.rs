@@ -0,0 +1,27 @@
+//! Fixtures shared by the tests.
+#![allow(dead_code)]
+use redb::{backends::InMemoryBackend, Database};
+use redb_model::Model;
+
+#[derive(Model, PartialEq, Eq, Debug, Clone)]
+#[model(impl_ext, patch, csv)]
+pub struct User {
+    #[entry(position = "key")]
+    pub id: u32,
+    #[entry(position = "value", redb_type = "&str")]
+    pub name: String,
+}
+
+pub fn user(id: u32, name: &str) -> User {
+    User {
+        id,
+        name: String::from(name),
+    }
+}
+
+/// Create an empty in-memory database.
+pub fn database() -> Database {
+    Database::builder()
+        .create_with_backend(InMemoryBackend::new())
+        .unwrap()
+}
@@ -0,0 +1,247 @@
+//! Database tests.
+mod common;
+
+use std::time::Duration;
+
+use redb::{backends::InMemoryBackend, ReadableTableMetadata};
+use redb_model::{schema, DatabaseExt, Error, Model, ModelDatabase, ModelSchema, Retry};
+
+#[derive(Model, PartialEq, Eq, Debug, Clone)]
+#[model(impl_ext)]
+struct Account {
+    #[entry(position = "key")]
+    id: u32,
+    #[entry(position = "value")]
+    balance: u64,
+    #[entry(position = "value", version)]
+    version: u64,
+}
+
+#[test]
+fn test_write() {
+    let db = common::database();
+
+    let id = db
+        .write(|txn| {
+            txn.insert(&mut Account {
+                id: 0,
+                balance: 10,
+                version: 0,
+            })?;
+            Ok(0)
+        })
+        .unwrap();
+    let account = db.write(|txn| txn.get::<Account>(id)).unwrap();
+    assert_eq!(account.map(|account| account.balance), Some(10));
+
+    // Abort the transaction of a failed closure.
+    let result = db.write(|txn| {
+        txn.remove::<Account>(0)?;
+        txn.update(&mut Account {
+            id: 1,
+            balance: 0,
+            version: 0,
+        })
+    });
+    assert!(matches!(result, Err(Error::NotFound)));
+    assert!(db.write(|txn| txn.get::<Account>(0)).unwrap().is_some());
+}
+
+#[test]
+fn test_write_retry() {
+    let db = common::database();
+    db.write(|txn| {
+        txn.insert(&mut Account {
+            id: 0,
+            balance: 10,
+            version: 0,
+        })
+    })
+    .unwrap();
+    let retry = Retry::new().backoff(Duration::ZERO);
+
+    // Retry a conflict, updating a stale model on the first attempt.
+    let mut attempts = 0;
+    let account = db
+        .write_with(&retry, |txn| {
+            attempts += 1;
+            let mut account = txn.get::<Account>(0)?.ok_or(Error::NotFound)?;
+            if attempts == 1 {
+                account.version += 1;
+            }
+            account.balance += 5;
+            txn.update(&mut account)?;
+            Ok(account)
+        })
+        .unwrap();
+    assert_eq!(attempts, 2);
+    assert_eq!(account.balance, 15);
+
+    // Return the last error once the attempts are exhausted.
+    let mut attempts = 0;
+    let result = db.write_with(&retry.attempts(4), |txn| {
+        attempts += 1;
+        txn.update(&mut Account {
+            id: 0,
+            balance: 0,
+            version: 0,
+        })
+    });
+    assert!(matches!(
+        result,
+        Err(Error::Conflict {
+            expected: 0,
+            found: 1
+        })
+    ));
+    assert_eq!(attempts, 4);
+
+    // Return errors that are not retried.
+    let mut attempts = 0;
+    let result = db.write_with(&retry, |txn| {
+        attempts += 1;
+        txn.update(&mut Account {
+            id: 1,
+            balance: 0,
+            version: 0,
+        })
+    });
+    assert!(matches!(result, Err(Error::NotFound)));
+    assert_eq!(attempts, 1);
+
+    let mut attempts = 0;
+    let result = db.write_with(&Retry::never(), |txn| {
+        attempts += 1;
+        txn.update(&mut Account {
+            id: 0,
+            balance: 0,
+            version: 0,
+        })
+    });
+    assert!(result.unwrap_err().is_transient());
+    assert_eq!(attempts, 1);
+}
+
+#[test]
+fn test_model_database() {
+    #[derive(Model, PartialEq, Eq, Debug, Clone)]
+    #[model(table_type = "multimap")]
+    struct Tag {
+        #[entry(position = "key")]
+        account: u32,
+        #[entry(position = "value", redb_type = "&str")]
+        tag: String,
+    }
+
+    let db = ModelDatabase::builder()
+        .register::<Account>()
+        .register::<Tag>()
+        .create_with_backend(InMemoryBackend::new())
+        .unwrap();
+
+    let txn = db.begin_read().unwrap();
+    assert_eq!(
+        txn.open_table(Account::DEFINITION).unwrap().len().unwrap(),
+        0
+    );
+    assert_eq!(
+        txn.open_multimap_table(Tag::DEFINITION)
+            .unwrap()
+            .len()
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        schema::get(&txn, "Tag")
+            .unwrap()
+            .map(|schema| schema.table_type),
+        Some(String::from("multimap"))
+    );
+
+    db.write(|txn| {
+        txn.insert(&mut Account {
+            id: 0,
+            balance: 0,
+            version: 0,
+        })
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_model_database_mismatch() {
+    #[derive(Model, PartialEq, Eq, Debug, Clone)]
+    #[model(name = "Account")]
+    struct LegacyAccount {
+        #[entry(position = "key")]
+        id: u64,
+        #[entry(position = "value")]
+        balance: u64,
+    }
+
+    let result = ModelDatabase::builder()
+        .register::<Account>()
+        .register::<LegacyAccount>()
+        .create_with_backend(InMemoryBackend::new());
+    assert!(matches!(
+        result,
+        Err(Error::Redb(e)) if matches!(*e, redb::Error::TableTypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_schema_hash() {
+    // The fields of `Account`, with the value fields reordered.
+    #[derive(Model, PartialEq, Eq, Debug, Clone)]
+    #[model(name = "Account", impl_ext)]
+    struct ReorderedAccount {
+        #[entry(position = "key")]
+        id: u32,
+        #[entry(position = "value")]
+        version: u64,
+        #[entry(position = "value")]
+        balance: u64,
+    }
+
+    assert_eq!(Account::SCHEMA_HASH, Account::SCHEMA.hash());
+    assert_ne!(Account::SCHEMA_HASH, ReorderedAccount::SCHEMA_HASH);
+
+    // Types are hashed by their normalized spelling.
+    let hash = |key_type, value_type| {
+        ModelSchema {
+            name: "Tag",
+            table_type: "table",
+            key_type,
+            value_type,
+            fields: &[],
+        }
+        .hash()
+    };
+    assert_eq!(
+        hash("String", "(u32, &str)"),
+        hash("std::string::String", "(u32,&'static str)")
+    );
+    assert_ne!(hash("String", "(u32, &str)"), hash("&str", "(u32, &str)"));
+
+    let db = common::database();
+    let txn = db.begin_write().unwrap();
+    schema::register::<Account>(&txn).unwrap();
+    txn.commit().unwrap();
+
+    let txn = db.begin_read().unwrap();
+    schema::check::<Account>(&txn).unwrap();
+    let result = schema::check::<ReorderedAccount>(&txn);
+    assert!(matches!(
+        result,
+        Err(Error::SchemaMismatch { table, expected, found })
+            if table == "Account"
+                && expected == ReorderedAccount::SCHEMA_HASH
+                && found == Account::SCHEMA_HASH
+    ));
+
+    let result = ModelDatabase::builder()
+        .register::<Account>()
+        .register::<ReorderedAccount>()
+        .create_with_backend(InMemoryBackend::new());
+    assert!(matches!(result, Err(Error::SchemaMismatch { .. })));
+}
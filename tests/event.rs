@@ -0,0 +1,180 @@
+//! Event store tests.
+mod common;
+
+use redb_model::{
+    history, Aggregate, Change, Error, EventStore, Model, ReadableModelTable, Subscribers, WriteTxn,
+};
+
+#[derive(Model, PartialEq, Eq, Debug, Clone)]
+#[model(impl_ext, history)]
+struct AccountEvent {
+    #[entry(position = "key")]
+    account: u32,
+    #[entry(position = "key")]
+    seq: u64,
+    #[entry(position = "value")]
+    amount: i64,
+}
+
+#[derive(Model, PartialEq, Eq, Debug, Clone, Default)]
+#[model(impl_ext, history)]
+struct Account {
+    #[entry(position = "key")]
+    id: u32,
+    #[entry(position = "value")]
+    balance: i64,
+    #[entry(position = "value", version)]
+    version: u64,
+}
+
+impl Aggregate<AccountEvent> for Account {
+    fn apply(&mut self, event: &AccountEvent) {
+        self.balance += event.amount;
+    }
+}
+
+fn events(amounts: &[i64]) -> Vec<AccountEvent> {
+    amounts
+        .iter()
+        .map(|&amount| AccountEvent {
+            account: 0,
+            seq: 0,
+            amount,
+        })
+        .collect()
+}
+
+#[test]
+fn test_event_store() {
+    let db = common::database();
+    let store = EventStore::<AccountEvent, Account>::new();
+
+    let txn = db.begin_read().unwrap();
+    assert_eq!(store.version(&txn, 1).unwrap(), 0);
+    let account = Account {
+        id: 1,
+        ..Default::default()
+    };
+    assert_eq!(store.load(&txn, 1).unwrap(), (account, 0));
+    drop(txn);
+
+    let txn = WriteTxn::new(db.begin_write().unwrap());
+    assert_eq!(store.append(&txn, 1, 0, events(&[10, -3, 5])).unwrap(), 3);
+    assert!(matches!(
+        store.append(&txn, 1, 2, events(&[1])),
+        Err(Error::Conflict {
+            expected: 2,
+            found: 3
+        })
+    ));
+    assert_eq!(store.append(&txn, 2, 0, events(&[7])).unwrap(), 1);
+    txn.commit().unwrap();
+
+    let txn = db.begin_read().unwrap();
+    assert_eq!(store.version(&txn, 1).unwrap(), 3);
+    assert_eq!(
+        store.events(&txn, 1, 1).unwrap(),
+        [
+            AccountEvent {
+                account: 1,
+                seq: 2,
+                amount: -3
+            },
+            AccountEvent {
+                account: 1,
+                seq: 3,
+                amount: 5
+            },
+        ]
+    );
+
+    let account = Account {
+        id: 1,
+        balance: 12,
+        version: 3,
+    };
+    assert_eq!(store.load(&txn, 1).unwrap(), (account, 3));
+    assert!(txn.open_table(Account::DEFINITION).is_err());
+}
+
+#[test]
+fn test_event_store_snapshot() {
+    let db = common::database();
+    let store = EventStore::<AccountEvent, Account>::new().snapshot_every(3);
+
+    let txn = WriteTxn::new(db.begin_write().unwrap());
+    store.append(&txn, 0, 0, events(&[1])).unwrap();
+    store.append(&txn, 0, 1, events(&[2, 3])).unwrap();
+    store.append(&txn, 0, 3, events(&[4])).unwrap();
+    txn.commit().unwrap();
+
+    // A snapshot is stored when the version reaches 3, and later events replayed.
+    let txn = db.begin_read().unwrap();
+    let snapshots = txn.open_table(Account::DEFINITION).unwrap();
+    let snapshot = Account {
+        id: 0,
+        balance: 6,
+        version: 3,
+    };
+    assert_eq!(snapshots.get_model::<Account>(0).unwrap(), Some(snapshot));
+
+    let account = Account {
+        id: 0,
+        balance: 10,
+        version: 4,
+    };
+    assert_eq!(store.load(&txn, 0).unwrap(), (account, 4));
+
+    // Appended events and snapshots are recorded in the history of each model.
+    assert_eq!(history::sequence::<AccountEvent>(&txn).unwrap(), 4);
+    assert_eq!(
+        history::history::<AccountEvent>(&txn, (0, 2))
+            .unwrap()
+            .len(),
+        1
+    );
+    let revisions = history::history::<Account>(&txn, 0).unwrap();
+    assert_eq!(revisions.len(), 1);
+    assert_eq!(revisions[0].previous, None);
+}
+
+#[test]
+fn test_event_store_txn() {
+    let db = common::database();
+    let store = EventStore::<AccountEvent, Account>::new().snapshot_every(2);
+    let subscribers = Subscribers::new();
+    let events_receiver = subscribers.subscribe::<AccountEvent>();
+    let snapshots_receiver = subscribers.subscribe::<Account>();
+
+    // The tables opened by the operations of the transaction are shared.
+    let txn = WriteTxn::with_subscribers(db.begin_write().unwrap(), &subscribers);
+    let mut event = AccountEvent {
+        account: 0,
+        seq: 1,
+        amount: 1,
+    };
+    txn.insert(&mut event).unwrap();
+    assert_eq!(store.append(&txn, 0, 1, events(&[2])).unwrap(), 2);
+    assert_eq!(txn.get::<AccountEvent>((0, 2)).unwrap().unwrap().amount, 2);
+    txn.commit().unwrap();
+
+    assert_eq!(
+        events_receiver.try_iter().collect::<Vec<_>>(),
+        [
+            Change::Insert(event),
+            Change::Insert(AccountEvent {
+                account: 0,
+                seq: 2,
+                amount: 2
+            }),
+        ]
+    );
+    assert_eq!(
+        snapshots_receiver.try_iter().collect::<Vec<_>>(),
+        [Change::Insert(Account {
+            id: 0,
+            balance: 3,
+            version: 2
+        })]
+    );
+}
@@ -0,0 +1,218 @@
+//! History tests.
+mod common;
+
+use redb::{Database, ReadableTableMetadata};
+use redb_model::history::{self, Revision};
+use redb_model::{Csv, Error, Model, ModelTable, WriteTxn};
+
+#[derive(Model, PartialEq, Eq, Debug, Clone)]
+#[model(impl_ext, history)]
+struct Account {
+    #[entry(position = "key")]
+    id: u32,
+    #[entry(position = "key", redb_type = "&str")]
+    currency: String,
+    #[entry(position = "value")]
+    balance: u64,
+}
+
+fn account(id: u32, balance: u64) -> Account {
+    Account {
+        id,
+        currency: String::from("EUR"),
+        balance,
+    }
+}
+
+#[test]
+fn test_history() {
+    let db = common::database();
+
+    let txn = WriteTxn::new(db.begin_write().unwrap());
+    txn.insert(&mut account(0, 10)).unwrap();
+    txn.update(&mut account(0, 20)).unwrap();
+    txn.insert(&mut account(1, 5)).unwrap();
+    txn.commit().unwrap();
+
+    let txn = WriteTxn::new(db.begin_write().unwrap());
+    txn.remove::<Account>((0, "EUR")).unwrap();
+    txn.commit().unwrap();
+
+    let txn = db.begin_read().unwrap();
+    assert_eq!(history::sequence::<Account>(&txn).unwrap(), 4);
+    assert_eq!(
+        history::history::<Account>(&txn, (0, "EUR")).unwrap(),
+        [
+            Revision {
+                seq: 1,
+                previous: None
+            },
+            Revision {
+                seq: 2,
+                previous: Some(account(0, 10))
+            },
+            Revision {
+                seq: 4,
+                previous: Some(account(0, 20))
+            },
+        ]
+    );
+    assert!(history::history::<Account>(&txn, (0, "USD"))
+        .unwrap()
+        .is_empty());
+
+    let as_of = |id, seq| history::as_of::<Account>(&txn, (id, "EUR"), seq).unwrap();
+    assert_eq!(as_of(0, 0), None);
+    assert_eq!(as_of(0, 1), Some(account(0, 10)));
+    assert_eq!(as_of(0, 2), Some(account(0, 20)));
+    assert_eq!(as_of(0, 3), Some(account(0, 20)));
+    assert_eq!(as_of(0, 4), None);
+    assert_eq!(as_of(1, 2), None);
+    assert_eq!(as_of(1, 3), Some(account(1, 5)));
+}
+
+#[test]
+fn test_history_undeclared() {
+    #[derive(Model, PartialEq, Eq, Debug, Clone)]
+    #[model(impl_ext)]
+    struct User {
+        #[entry(position = "key")]
+        id: u32,
+    }
+
+    let db = common::database();
+
+    let txn = WriteTxn::new(db.begin_write().unwrap());
+    txn.insert(&mut User { id: 0 }).unwrap();
+    txn.commit().unwrap();
+
+    let txn = db.begin_read().unwrap();
+    assert_eq!(history::sequence::<User>(&txn).unwrap(), 0);
+    assert!(history::history::<User>(&txn, 0).unwrap().is_empty());
+    assert_eq!(
+        history::as_of::<User>(&txn, 0, 0).unwrap(),
+        Some(User { id: 0 })
+    );
+}
+
+#[derive(Model, PartialEq, Eq, Debug, Clone)]
+#[model(impl_ext, patch, soft_delete, csv, history)]
+struct Note {
+    #[entry(position = "key")]
+    id: u32,
+    #[entry(position = "value", redb_type = "&str")]
+    text: String,
+}
+
+fn note(id: u32, text: &str) -> Note {
+    Note {
+        id,
+        text: String::from(text),
+    }
+}
+
+/// Get the sequence numbers of the changes to the note, and the previous text.
+fn revisions(db: &Database, id: u32) -> Vec<(u64, Option<String>)> {
+    let txn = db.begin_read().unwrap();
+    history::history::<Note>(&txn, id)
+        .unwrap()
+        .into_iter()
+        .map(|revision| (revision.seq, revision.previous.map(|note| note.text)))
+        .collect()
+}
+
+#[test]
+fn test_history_table_writes() {
+    let db = common::database();
+
+    let txn = WriteTxn::new(db.begin_write().unwrap());
+    txn.insert(&mut note(0, "a")).unwrap();
+    txn.soft_remove::<Note>(0).unwrap();
+    txn.insert(&mut note(1, "b")).unwrap();
+    txn.commit().unwrap();
+
+    let txn = db.begin_write().unwrap();
+    {
+        let mut table = txn.open_table(Note::DEFINITION).unwrap();
+        let patch = || NotePatch {
+            text: Some(String::from("c")),
+        };
+        let results = [
+            table.insert_model(&mut note(2, "c")).map(drop),
+            table.update_model(&mut note(1, "c")).map(drop),
+            table.replace_model(&note(1, "c")).map(drop),
+            table.take_model::<Note>(1).map(drop),
+            table.insert_many([note(2, "c")], false).map(drop),
+            table.upsert_many([note(1, "c")], false).map(drop),
+            table.patch_model::<Note>(1, patch()).map(drop),
+            table.remove_model::<Note>(1).map(drop),
+            table.soft_remove_model::<Note>(1).map(drop),
+            table.restore_model::<Note>(0).map(drop),
+        ];
+        for result in results {
+            assert!(matches!(
+                result,
+                Err(Error::HistoryNotRecorded {
+                    history: "Note_history"
+                })
+            ));
+        }
+        assert_eq!(table.purge_models::<Note>().unwrap(), 1);
+    }
+    txn.commit().unwrap();
+
+    let txn = db.begin_read().unwrap();
+    let table = txn.open_table(Note::DEFINITION).unwrap();
+    assert_eq!(table.len().unwrap(), 1);
+    assert_eq!(history::sequence::<Note>(&txn).unwrap(), 3);
+}
+
+#[test]
+fn test_history_txn_writes() {
+    let db = common::database();
+
+    let txn = WriteTxn::new(db.begin_write().unwrap());
+    let count = txn.insert_many([note(0, "a"), note(1, "a")], true).unwrap();
+    assert_eq!(count.inserted, 2);
+    txn.upsert_many([note(0, "b"), note(2, "b")], true).unwrap();
+    txn.replace(&note(1, "c")).unwrap();
+    let patch = NotePatch {
+        text: Some(String::from("d")),
+    };
+    txn.patch::<Note>(1, patch).unwrap();
+    txn.soft_remove::<Note>(1).unwrap();
+    txn.restore::<Note>(1).unwrap();
+    txn.take::<Note>(2).unwrap();
+    txn.soft_remove::<Note>(0).unwrap();
+    assert_eq!(txn.purge::<Note>().unwrap(), 1);
+    txn.commit().unwrap();
+
+    let text = |text: &str| Some(String::from(text));
+    assert_eq!(
+        revisions(&db, 0),
+        [(1, None), (3, text("a")), (10, text("b"))]
+    );
+    assert_eq!(
+        revisions(&db, 1),
+        [
+            (2, None),
+            (5, text("a")),
+            (6, text("c")),
+            (7, text("d")),
+            (8, None)
+        ]
+    );
+    assert_eq!(revisions(&db, 2), [(4, None), (9, text("b"))]);
+}
+
+#[test]
+fn test_history_import() {
+    let db = common::database();
+
+    let txn = db.begin_write().unwrap();
+    let csv = "id,text\n0,a\n0,b\n";
+    assert_eq!(Csv::new().import::<Note>(&txn, csv.as_bytes()).unwrap(), 2);
+    txn.commit().unwrap();
+
+    assert_eq!(revisions(&db, 0), [(1, None), (2, Some(String::from("a")))]);
+}
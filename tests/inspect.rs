@@ -0,0 +1,407 @@
+//! Schema registry and inspection tests.
+mod common;
+
+use std::env;
+use std::net::{IpAddr, Ipv4Addr};
+use std::process::Command;
+
+use redb::{Database, TableDefinition, TypeName, Value};
+use redb_model::inspect::Inspector;
+use redb_model::schema::{self, TableField};
+use redb_model::{FieldSchema, Model, ModelSchema, ModelTable};
+
+#[derive(Model, PartialEq, Eq, Debug, Clone)]
+#[model(impl_ext, csv, name = "edges")]
+struct Edge {
+    #[entry(position = "key")]
+    source: u32,
+    #[entry(position = "key", redb_type = "&str")]
+    label: String,
+    #[entry(position = "value")]
+    target: u32,
+}
+
+fn edge(source: u32, label: &str, target: u32) -> Edge {
+    Edge {
+        source,
+        label: String::from(label),
+        target,
+    }
+}
+
+fn database() -> Database {
+    let db = common::database();
+
+    let txn = db.begin_write().unwrap();
+    schema::register::<Edge>(&txn).unwrap();
+    {
+        let mut table = txn.open_table(Edge::DEFINITION).unwrap();
+        table.insert_model(&mut edge(0, "a", 1)).unwrap();
+        table.insert_model(&mut edge(0, "b", 2)).unwrap();
+        table.insert_model(&mut edge(1, "a", 2)).unwrap();
+    }
+    txn.commit().unwrap();
+    db
+}
+
+fn inspect(inspector: &Inspector, db: &Database, args: &[&str]) -> Result<String, String> {
+    let mut out = Vec::new();
+    inspector
+        .inspect(db, args.iter().map(|arg| arg.to_string()), &mut out)
+        .map_err(|e| e.to_string())?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn test_schema() {
+    assert_eq!(
+        Edge::SCHEMA,
+        ModelSchema {
+            name: "edges",
+            table_type: "table",
+            key_type: "(u32, &'static str)",
+            value_type: "u32",
+            fields: &[
+                FieldSchema {
+                    name: "source",
+                    key: true,
+                    ty: "u32",
+                    redb_type: "u32",
+                },
+                FieldSchema {
+                    name: "label",
+                    key: true,
+                    ty: "String",
+                    redb_type: "&'static str",
+                },
+                FieldSchema {
+                    name: "target",
+                    key: false,
+                    ty: "u32",
+                    redb_type: "u32",
+                },
+            ],
+        }
+    );
+
+    let db = database();
+    let txn = db.begin_read().unwrap();
+    let registered = schema::get(&txn, "edges").unwrap().unwrap();
+    assert_eq!(registered, Edge::SCHEMA.into());
+    assert_eq!(
+        registered.fields[1],
+        TableField {
+            name: String::from("label"),
+            key: true,
+            ty: String::from("String"),
+            redb_type: String::from("&'static str"),
+        }
+    );
+    assert_eq!(schema::all(&txn).unwrap(), [registered]);
+    assert_eq!(schema::get(&txn, "nodes").unwrap(), None);
+}
+
+#[test]
+fn test_inspect() {
+    let db = database();
+    let inspector = Inspector::new();
+
+    assert_eq!(
+        inspect(&inspector, &db, &["tables"]).unwrap(),
+        "\
+table  type   rows  key                  value
+-----  -----  ----  -------------------  -----
+edges  table  3     (u32, &'static str)  u32
+"
+    );
+    assert_eq!(
+        inspect(&inspector, &db, &["schema", "edges", "--json"]).unwrap(),
+        r#"[
+  {"field": "source", "position": "key", "type": "u32", "redb_type": "u32"},
+  {"field": "label", "position": "key", "type": "String", "redb_type": "&'static str"},
+  {"field": "target", "position": "value", "type": "u32", "redb_type": "u32"}
+]
+"#
+    );
+    assert_eq!(
+        inspect(&inspector, &db, &["rows", "edges", "--from", "0,b"]).unwrap(),
+        "\
+source  label  target
+------  -----  ------
+0       b      2
+1       a      2
+"
+    );
+    assert_eq!(
+        inspect(&inspector, &db, &["rows", "nodes"]),
+        Err(String::from("no schema is registered for `nodes`"))
+    );
+    assert_eq!(
+        inspect(&inspector, &db, &["drop", "edges"]),
+        Err(String::from("unknown command `drop`"))
+    );
+}
+
+#[test]
+fn test_inspect_rows() {
+    let db = database();
+    let inspector = Inspector::new().register::<Edge>();
+
+    assert_eq!(
+        inspect(&inspector, &db, &["rows", "edges"]).unwrap(),
+        "\
+source  label  target
+------  -----  ------
+0       a      1
+0       b      2
+1       a      2
+"
+    );
+    assert_eq!(
+        inspect(
+            &inspector,
+            &db,
+            &["rows", "edges", "--from", "0,b", "--to", "1,b", "--json"]
+        )
+        .unwrap(),
+        r#"[
+  {"source": 0, "label": "b", "target": 2},
+  {"source": 1, "label": "a", "target": 2}
+]
+"#
+    );
+    assert_eq!(
+        inspect(
+            &inspector,
+            &db,
+            &["rows", "edges", "--limit", "1", "--json"]
+        )
+        .unwrap(),
+        "[\n  {\"source\": 0, \"label\": \"a\", \"target\": 1}\n]\n"
+    );
+    assert_eq!(
+        inspect(&inspector, &db, &["rows", "edges", "--from", "0"]),
+        Err(String::from("expected 2 key fields, found 1"))
+    );
+}
+
+#[test]
+fn test_inspect_decoded_rows() {
+    #[derive(Model, PartialEq, Debug, Clone)]
+    #[model(impl_ext, infer_borrowed, name = "readings", soft_delete)]
+    struct Reading {
+        #[entry(position = "key")]
+        id: i16,
+        #[entry(position = "value")]
+        sensor: Option<u16>,
+        #[entry(position = "value")]
+        value: f64,
+        #[entry(position = "value")]
+        flags: (bool, char),
+        #[entry(position = "value")]
+        raw: Vec<u8>,
+    }
+
+    let db = common::database();
+    let txn = db.begin_write().unwrap();
+    schema::register::<Reading>(&txn).unwrap();
+    {
+        let mut table = txn.open_table(Reading::DEFINITION).unwrap();
+        for (id, sensor) in [(-2, None), (1, Some(7)), (3, Some(8))] {
+            table
+                .insert_model(&mut Reading {
+                    id,
+                    sensor,
+                    value: 0.5,
+                    flags: (true, 'x'),
+                    raw: vec![0x0a, 0xff],
+                })
+                .unwrap();
+        }
+        table.soft_remove_model::<Reading>(3).unwrap();
+    }
+    txn.commit().unwrap();
+
+    assert_eq!(
+        inspect(&Inspector::new(), &db, &["rows", "readings", "--to", "3"]).unwrap(),
+        "\
+id  sensor  value  flags      raw
+--  ------  -----  ---------  ----
+-2          0.5    (true, x)  0aff
+1   7       0.5    (true, x)  0aff
+"
+    );
+    assert_eq!(
+        inspect(&Inspector::new(), &db, &["rows", "readings", "--from", "x"]),
+        Err(String::from(
+            "invalid `i16` key `x`: invalid digit found in string"
+        ))
+    );
+}
+
+#[test]
+fn test_inspect_binary() {
+    let path = env::temp_dir().join(format!("redb-model-inspect-{}.redb", std::process::id()));
+    {
+        let db = Database::create(&path).unwrap();
+        let txn = db.begin_write().unwrap();
+        schema::register::<Edge>(&txn).unwrap();
+        {
+            let mut table = txn.open_table(Edge::DEFINITION).unwrap();
+            table.insert_model(&mut edge(0, "a", 1)).unwrap();
+            table.insert_model(&mut edge(1, "b", 2)).unwrap();
+        }
+        txn.commit().unwrap();
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_redb-model"))
+        .arg(&path)
+        .args(["rows", "edges", "--json"])
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        r#"[
+  {"source": 0, "label": "a", "target": 1},
+  {"source": 1, "label": "b", "target": 2}
+]
+"#
+    );
+}
+
+#[test]
+fn test_inspect_stored_types() {
+    /// A value of a type that is not decoded, printed as hexadecimal.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Point(u8, u8);
+
+    impl Value for Point {
+        type SelfType<'a> = Point;
+        type AsBytes<'a> = [u8; 2];
+
+        fn fixed_width() -> Option<usize> {
+            Some(2)
+        }
+
+        fn from_bytes<'a>(data: &'a [u8]) -> Point
+        where
+            Self: 'a,
+        {
+            Point(data[0], data[1])
+        }
+
+        fn as_bytes<'a, 'b: 'a>(value: &'a Point) -> [u8; 2]
+        where
+            Self: 'a,
+            Self: 'b,
+        {
+            [value.0, value.1]
+        }
+
+        fn type_name() -> TypeName {
+            TypeName::new("test::Point")
+        }
+    }
+
+    #[derive(Model, PartialEq, Debug, Clone)]
+    #[model(impl_ext, name = "samples")]
+    struct Sample {
+        #[entry(position = "key")]
+        id: u32,
+        #[entry(position = "value", adapter = "net")]
+        address: IpAddr,
+        #[entry(position = "value")]
+        tag: [u8; 2],
+        #[entry(position = "value")]
+        point: Point,
+        #[entry(position = "value")]
+        note: Option<u16>,
+    }
+
+    let db = common::database();
+    let txn = db.begin_write().unwrap();
+    schema::register::<Sample>(&txn).unwrap();
+    {
+        let mut table = txn.open_table(Sample::DEFINITION).unwrap();
+        table
+            .insert_model(&mut Sample {
+                id: 1,
+                address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+                tag: [0x0a, 0x0b],
+                point: Point(1, 2),
+                note: None,
+            })
+            .unwrap();
+    }
+    txn.commit().unwrap();
+
+    assert_eq!(
+        inspect(&Inspector::new(), &db, &["rows", "samples"]).unwrap(),
+        "\
+id  address          tag   point  note
+--  ---------------  ----  -----  ----
+1   (4, 2130706433)  0a0b  0102
+"
+    );
+    assert_eq!(
+        inspect(&Inspector::new(), &db, &["rows", "samples", "--json"]).unwrap(),
+        r#"[
+  {"id": 1, "address": [4, 2130706433], "tag": "0a0b", "point": "0102", "note": null}
+]
+"#
+    );
+}
+
+#[test]
+fn test_inspect_invalid_rows() {
+    /// A value stored under the name of `char`, which is not a valid `char`.
+    #[derive(Debug)]
+    struct Invalid;
+
+    impl Value for Invalid {
+        type SelfType<'a> = ();
+        type AsBytes<'a> = [u8; 3];
+
+        fn fixed_width() -> Option<usize> {
+            Some(3)
+        }
+
+        fn from_bytes<'a>(_: &'a [u8]) -> Self::SelfType<'a>
+        where
+            Self: 'a,
+        {
+        }
+
+        fn as_bytes<'a, 'b: 'a>(_: &'a ()) -> [u8; 3]
+        where
+            Self: 'a,
+            Self: 'b,
+        {
+            [0xff; 3]
+        }
+
+        fn type_name() -> TypeName {
+            TypeName::new("char")
+        }
+    }
+
+    let db = database();
+    let txn = db.begin_write().unwrap();
+    txn.delete_table(Edge::DEFINITION).unwrap();
+    {
+        let definition = TableDefinition::<(u32, &str), Invalid>::new("edges");
+        let mut table = txn.open_table(definition).unwrap();
+        table.insert((0, "a"), ()).unwrap();
+    }
+    txn.commit().unwrap();
+
+    assert_eq!(
+        inspect(&Inspector::new(), &db, &["rows", "edges"]),
+        Err(String::from(
+            "invalid row of `edges`: invalid `char` of 3 bytes"
+        ))
+    );
+}
@@ -0,0 +1,530 @@
+//! Table operation tests.
+mod common;
+
+use std::cell::Cell;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use redb_model::{
+    BatchCount, Clock, Error, Model, ModelExt, ModelHooks, ModelPatch, ModelTable,
+    ReadableModelTable,
+};
+
+use common::{user, User};
+
+#[test]
+fn test_insert_get_remove() {
+    let db = common::database();
+
+    let txn = db.begin_write().unwrap();
+    {
+        let mut table = txn.open_table(User::DEFINITION).unwrap();
+        assert_eq!(table.insert_model(&mut user(0, "a")).unwrap(), None);
+        assert_eq!(
+            table.insert_model(&mut user(0, "b")).unwrap(),
+            Some(user(0, "a"))
+        );
+    }
+    txn.commit().unwrap();
+
+    let txn = db.begin_read().unwrap();
+    let table = txn.open_table(User::DEFINITION).unwrap();
+    assert_eq!(table.get_model::<User>(0).unwrap(), Some(user(0, "b")));
+    assert_eq!(table.get_model::<User>(1).unwrap(), None);
+
+    let txn = db.begin_write().unwrap();
+    {
+        let mut table = txn.open_table(User::DEFINITION).unwrap();
+        assert_eq!(table.remove_model::<User>(0).unwrap(), Some(user(0, "b")));
+        assert_eq!(table.remove_model::<User>(0).unwrap(), None);
+    }
+    txn.commit().unwrap();
+}
+
+#[test]
+fn test_replace_take() {
+    let db = common::database();
+
+    let txn = db.begin_write().unwrap();
+    let mut table = txn.open_table(User::DEFINITION).unwrap();
+    assert_eq!(table.replace_model(&user(0, "a")).unwrap(), None);
+
+    // Undo an insertion by replacing the previous model.
+    let previous = table.insert_model(&mut user(0, "b")).unwrap().unwrap();
+    assert_eq!(table.replace_model(&previous).unwrap(), Some(user(0, "b")));
+    assert_eq!(table.get_model::<User>(0).unwrap(), Some(user(0, "a")));
+
+    assert_eq!(table.take_model::<User>(0).unwrap(), Some(user(0, "a")));
+    assert_eq!(table.take_model::<User>(0).unwrap(), None);
+    assert_eq!(table.get_model::<User>(0).unwrap(), None);
+}
+
+#[test]
+fn test_range() {
+    let db = common::database();
+
+    let txn = db.begin_write().unwrap();
+    {
+        let mut table = txn.open_table(User::DEFINITION).unwrap();
+        for id in 0..4 {
+            table.insert_model(&mut user(id, "user")).unwrap();
+        }
+    }
+    txn.commit().unwrap();
+
+    let txn = db.begin_read().unwrap();
+    let table = txn.open_table(User::DEFINITION).unwrap();
+    let ids = table
+        .range_models::<User, u32>(1..3)
+        .unwrap()
+        .map(|user| user.unwrap().id)
+        .collect::<Vec<_>>();
+    assert_eq!(ids, [1, 2]);
+
+    let ids = table
+        .iter_models::<User>()
+        .unwrap()
+        .rev()
+        .map(|user| user.unwrap().id)
+        .collect::<Vec<_>>();
+    assert_eq!(ids, [3, 2, 1, 0]);
+}
+
+#[test]
+fn test_validate() {
+    fn non_empty(value: &str) -> Result<(), &'static str> {
+        match value.is_empty() {
+            true => Err("must not be empty"),
+            false => Ok(()),
+        }
+    }
+
+    #[derive(Model, Debug)]
+    #[model(impl_ext)]
+    struct ValidatedUser {
+        #[entry(position = "key")]
+        id: u32,
+        #[entry(position = "value", redb_type = "&str", validate = "non_empty")]
+        name: String,
+    }
+
+    let db = common::database();
+
+    let txn = db.begin_write().unwrap();
+    let mut table = txn.open_table(ValidatedUser::DEFINITION).unwrap();
+    let mut user = ValidatedUser {
+        id: 0,
+        name: String::new(),
+    };
+    match table.insert_model(&mut user) {
+        Err(Error::Validation(e)) => assert_eq!(e.field_name(), Some("name")),
+        _ => panic!("expected a validation error"),
+    }
+    assert!(table.get_model::<ValidatedUser>(0).unwrap().is_none());
+}
+
+#[test]
+fn test_hooks() {
+    thread_local! {
+        static REMOVED: Cell<u32> = const { Cell::new(0) };
+    }
+
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext, hooks)]
+    struct HookedUser {
+        #[entry(position = "key")]
+        id: u32,
+        #[entry(position = "value", redb_type = "&str")]
+        name: String,
+        #[entry(position = "value")]
+        loads: u32,
+    }
+
+    impl ModelHooks for HookedUser {
+        fn before_insert(&mut self) {
+            self.name = self.name.trim().to_lowercase();
+        }
+
+        fn after_load(&mut self) {
+            self.loads += 1;
+        }
+
+        fn before_remove(&self) {
+            REMOVED.with(|removed| removed.set(removed.get() + 1));
+        }
+    }
+
+    // Hooks run on conversion methods.
+    let user = HookedUser::from_values((0, ("name", 0)));
+    assert_eq!(user.loads, 1);
+
+    let db = common::database();
+
+    let txn = db.begin_write().unwrap();
+    {
+        let mut table = txn.open_table(HookedUser::DEFINITION).unwrap();
+        let mut user = HookedUser {
+            id: 0,
+            name: String::from(" Name "),
+            loads: 0,
+        };
+        table.insert_model(&mut user).unwrap();
+        assert_eq!(user.name, "name");
+
+        let user = table.get_model::<HookedUser>(0).unwrap().unwrap();
+        assert_eq!(user.name, "name");
+        assert_eq!(user.loads, 1);
+
+        table.remove_model::<HookedUser>(0).unwrap();
+        assert_eq!(REMOVED.with(Cell::get), 1);
+    }
+    txn.commit().unwrap();
+}
+
+#[test]
+fn test_timestamps() {
+    struct FixedClock(u64);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> SystemTime {
+            UNIX_EPOCH + Duration::from_secs(self.0)
+        }
+    }
+
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext)]
+    struct AuditedUser {
+        #[entry(position = "key")]
+        id: u32,
+        #[entry(position = "value", adapter = "std_time", created_at)]
+        created: SystemTime,
+        #[entry(position = "value", adapter = "std_time", updated_at)]
+        updated: Duration,
+    }
+
+    let db = common::database();
+
+    let txn = db.begin_write().unwrap();
+    let mut table = txn.open_table(AuditedUser::DEFINITION).unwrap();
+    let mut user = AuditedUser {
+        id: 0,
+        created: UNIX_EPOCH,
+        updated: Duration::ZERO,
+    };
+
+    assert!(matches!(
+        table.update_model_with(&mut user, &FixedClock(1)),
+        Err(Error::NotFound)
+    ));
+
+    table.insert_model_with(&mut user, &FixedClock(1)).unwrap();
+    assert_eq!(user.created, FixedClock(1).now());
+    assert_eq!(user.updated, Duration::from_secs(1));
+
+    let previous = table.update_model_with(&mut user, &FixedClock(2)).unwrap();
+    assert_eq!(previous.updated, Duration::from_secs(1));
+    assert_eq!(user.created, FixedClock(1).now());
+    assert_eq!(user.updated, Duration::from_secs(2));
+
+    let user = table.get_model::<AuditedUser>(0).unwrap().unwrap();
+    assert_eq!(user.created, FixedClock(1).now());
+    assert_eq!(user.updated, Duration::from_secs(2));
+
+    // The stored `created_at` is kept by updates and insertions of new models.
+    let mut user = AuditedUser {
+        id: 0,
+        created: UNIX_EPOCH,
+        updated: Duration::ZERO,
+    };
+    table.update_model_with(&mut user, &FixedClock(3)).unwrap();
+    assert_eq!(user.created, FixedClock(1).now());
+    assert_eq!(user.updated, Duration::from_secs(3));
+
+    let mut user = AuditedUser {
+        id: 0,
+        created: UNIX_EPOCH,
+        updated: Duration::ZERO,
+    };
+    table.insert_model_with(&mut user, &FixedClock(4)).unwrap();
+    assert_eq!(user.created, FixedClock(1).now());
+    assert_eq!(user.updated, Duration::from_secs(4));
+
+    let user = table.get_model::<AuditedUser>(0).unwrap().unwrap();
+    assert_eq!(user.created, FixedClock(1).now());
+    assert_eq!(user.updated, Duration::from_secs(4));
+}
+
+#[test]
+fn test_insert_many() {
+    struct FixedClock(u64);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> SystemTime {
+            UNIX_EPOCH + Duration::from_secs(self.0)
+        }
+    }
+
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext)]
+    struct AuditedUser {
+        #[entry(position = "key")]
+        id: u32,
+        #[entry(position = "value", redb_type = "&str")]
+        name: String,
+        #[entry(position = "value", adapter = "std_time", created_at)]
+        created: SystemTime,
+        #[entry(position = "value", adapter = "std_time", updated_at)]
+        updated: SystemTime,
+    }
+
+    fn users(ids: &[u32], name: &str) -> Vec<AuditedUser> {
+        ids.iter()
+            .map(|&id| AuditedUser {
+                id,
+                name: String::from(name),
+                created: UNIX_EPOCH,
+                updated: UNIX_EPOCH,
+            })
+            .collect()
+    }
+
+    let db = common::database();
+
+    let txn = db.begin_write().unwrap();
+    let mut table = txn.open_table(AuditedUser::DEFINITION).unwrap();
+
+    let count = table
+        .insert_many_with(users(&[3, 1, 2], "a"), true, &FixedClock(1))
+        .unwrap();
+    assert_eq!(
+        count,
+        BatchCount {
+            inserted: 3,
+            replaced: 0
+        }
+    );
+
+    let count = table
+        .insert_many_with(users(&[2, 4], "b"), false, &FixedClock(2))
+        .unwrap();
+    assert_eq!(
+        count,
+        BatchCount {
+            inserted: 1,
+            replaced: 1
+        }
+    );
+    let user = table.get_model::<AuditedUser>(2).unwrap().unwrap();
+    assert_eq!(user.name, "b");
+    assert_eq!(user.created, FixedClock(2).now());
+
+    let count = table
+        .upsert_many_with(users(&[5, 1], "c"), true, &FixedClock(3))
+        .unwrap();
+    assert_eq!(
+        count,
+        BatchCount {
+            inserted: 1,
+            replaced: 1
+        }
+    );
+    let user = table.get_model::<AuditedUser>(1).unwrap().unwrap();
+    assert_eq!(user.name, "c");
+    assert_eq!(
+        (user.created, user.updated),
+        (FixedClock(1).now(), FixedClock(3).now())
+    );
+    let user = table.get_model::<AuditedUser>(5).unwrap().unwrap();
+    assert_eq!(
+        (user.created, user.updated),
+        (FixedClock(3).now(), FixedClock(3).now())
+    );
+
+    let ids = table
+        .iter_models::<AuditedUser>()
+        .unwrap()
+        .map(|user| user.unwrap().id)
+        .collect::<Vec<_>>();
+    assert_eq!(ids, [1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn test_patch() {
+    #[derive(Model, PartialEq, Eq, Debug, Clone)]
+    #[model(impl_ext, patch, patch_derive(Debug, Clone, PartialEq))]
+    struct Profile {
+        #[entry(position = "key")]
+        id: u32,
+        #[entry(position = "value", redb_type = "&str")]
+        name: String,
+        #[entry(position = "value")]
+        age: u8,
+        #[entry(position = "value", version)]
+        version: u64,
+        #[entry(position = "value", adapter = "std_time", created_at)]
+        created: SystemTime,
+        #[entry(position = "value", adapter = "std_time", updated_at)]
+        updated: SystemTime,
+    }
+
+    let mut profile = Profile {
+        id: 0,
+        name: String::from("a"),
+        age: 20,
+        version: 0,
+        created: UNIX_EPOCH,
+        updated: UNIX_EPOCH,
+    };
+    profile.apply_patch(ProfilePatch::default());
+    assert_eq!(profile.name, "a");
+
+    // The `version`, `created_at` and `updated_at` fields are not patched.
+    let ProfilePatch { name: _, age: _ } = ProfilePatch::default();
+
+    let db = common::database();
+
+    let txn = db.begin_write().unwrap();
+    let mut table = txn.open_table(Profile::DEFINITION).unwrap();
+    assert!(matches!(
+        table.patch_model::<Profile>(0, ProfilePatch::default()),
+        Err(Error::NotFound)
+    ));
+
+    table.insert_model(&mut profile).unwrap();
+    let patch = ProfilePatch {
+        age: Some(21),
+        ..Default::default()
+    };
+    let patched = table.patch_model::<Profile>(0, patch.clone()).unwrap();
+    assert_eq!((patched.name.as_str(), patched.age), ("a", 21));
+    assert_eq!(patched.version, 1);
+    assert_eq!(table.get_model::<Profile>(0).unwrap(), Some(patched));
+}
+
+#[test]
+fn test_soft_delete() {
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext, soft_delete)]
+    struct Post {
+        #[entry(position = "key")]
+        id: u32,
+        #[entry(position = "value", redb_type = "&str")]
+        title: String,
+    }
+
+    fn post(id: u32, title: &str) -> Post {
+        Post {
+            id,
+            title: String::from(title),
+        }
+    }
+
+    let db = common::database();
+
+    let txn = db.begin_write().unwrap();
+    let mut table = txn.open_table(Post::DEFINITION).unwrap();
+    for id in 0..3 {
+        table.insert_model(&mut post(id, "post")).unwrap();
+    }
+
+    assert_eq!(
+        table.soft_remove_model::<Post>(1).unwrap(),
+        Some(post(1, "post"))
+    );
+    assert_eq!(table.soft_remove_model::<Post>(1).unwrap(), None);
+    assert_eq!(table.get_model::<Post>(1).unwrap(), None);
+    assert_eq!(
+        table.get_model_with_deleted::<Post>(1).unwrap(),
+        Some(post(1, "post"))
+    );
+    assert!(matches!(
+        table.update_model(&mut post(1, "updated")),
+        Err(Error::NotFound)
+    ));
+
+    fn ids(iter: impl Iterator<Item = Result<Post, Error>>) -> Vec<u32> {
+        iter.map(|post| post.unwrap().id).collect()
+    }
+    assert_eq!(ids(table.iter_models::<Post>().unwrap()), [0, 2]);
+    assert_eq!(ids(table.iter_models::<Post>().unwrap().rev()), [2, 0]);
+    assert_eq!(
+        ids(table.iter_models_with_deleted::<Post>().unwrap()),
+        [0, 1, 2]
+    );
+
+    assert_eq!(
+        table.restore_model::<Post>(1).unwrap(),
+        Some(post(1, "post"))
+    );
+    assert_eq!(table.restore_model::<Post>(1).unwrap(), None);
+    assert_eq!(ids(table.iter_models::<Post>().unwrap()), [0, 1, 2]);
+
+    table.soft_remove_model::<Post>(0).unwrap();
+    table.soft_remove_model::<Post>(2).unwrap();
+    assert_eq!(table.insert_model(&mut post(2, "new")).unwrap(), None);
+    assert_eq!(table.purge_models::<Post>().unwrap(), 1);
+    assert_eq!(table.get_model_with_deleted::<Post>(0).unwrap(), None);
+    assert_eq!(
+        ids(table.iter_models_with_deleted::<Post>().unwrap()),
+        [1, 2]
+    );
+
+    table.soft_remove_model::<Post>(1).unwrap();
+    assert_eq!(table.take_model::<Post>(1).unwrap(), None);
+    assert_eq!(table.get_model_with_deleted::<Post>(1).unwrap(), None);
+    assert_eq!(table.take_model::<Post>(2).unwrap(), Some(post(2, "new")));
+}
+
+#[test]
+fn test_version() {
+    #[derive(Model, PartialEq, Eq, Debug, Clone)]
+    #[model(impl_ext)]
+    struct Counter {
+        #[entry(position = "key")]
+        id: u32,
+        #[entry(position = "value")]
+        count: u32,
+        #[entry(position = "value", version)]
+        version: u64,
+    }
+
+    let db = common::database();
+
+    let txn = db.begin_write().unwrap();
+    {
+        let mut table = txn.open_table(Counter::DEFINITION).unwrap();
+        let mut counter = Counter {
+            id: 0,
+            count: 0,
+            version: 0,
+        };
+        table.insert_model(&mut counter).unwrap();
+    }
+    txn.commit().unwrap();
+
+    let txn = db.begin_read().unwrap();
+    let table = txn.open_table(Counter::DEFINITION).unwrap();
+    let mut a = table.get_model::<Counter>(0).unwrap().unwrap();
+    let mut b = a.clone();
+    drop((table, txn));
+
+    let txn = db.begin_write().unwrap();
+    {
+        let mut table = txn.open_table(Counter::DEFINITION).unwrap();
+        a.count += 1;
+        let previous = table.update_model(&mut a).unwrap();
+        assert_eq!(previous.version, 0);
+        assert_eq!(a.version, 1);
+
+        b.count += 2;
+        assert!(matches!(
+            table.update_model(&mut b),
+            Err(Error::Conflict {
+                expected: 0,
+                found: 1
+            })
+        ));
+        assert_eq!(b.version, 0);
+        assert_eq!(table.get_model::<Counter>(0).unwrap(), Some(a.clone()));
+    }
+    txn.commit().unwrap();
+}
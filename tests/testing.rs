@@ -0,0 +1,133 @@
+//! Round-trip assertion tests.
+#![cfg(feature = "testing")]
+use redb_model::testing::assert_roundtrip;
+use redb_model::{Model, ModelHooks};
+
+#[derive(Model, PartialEq, Eq, Debug, Clone)]
+#[model(impl_ext, soft_delete)]
+struct Edge {
+    #[entry(position = "key")]
+    source: u32,
+    #[entry(position = "key", redb_type = "&str")]
+    label: String,
+    #[entry(position = "value")]
+    target: u64,
+    #[entry(position = "value", redb_type = "&str")]
+    note: String,
+}
+
+fn edges() -> impl Iterator<Item = Edge> {
+    [(0, "", 0, ""), (u32::MAX, "label", u64::MAX, "é")]
+        .into_iter()
+        .map(|(source, label, target, note)| Edge {
+            source,
+            label: String::from(label),
+            target,
+            note: String::from(note),
+        })
+}
+
+#[cfg(feature = "proptest")]
+impl proptest::arbitrary::Arbitrary for Edge {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        use proptest::prelude::*;
+
+        let string = || proptest::collection::vec(any::<char>(), 0..8).prop_map(String::from_iter);
+        (any::<u32>(), string(), any::<u64>(), string())
+            .prop_map(|(source, label, target, note)| Edge {
+                source,
+                label,
+                target,
+                note,
+            })
+            .boxed()
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl quickcheck::Arbitrary for Edge {
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        Edge {
+            source: u32::arbitrary(g),
+            label: String::arbitrary(g),
+            target: u64::arbitrary(g),
+            note: String::arbitrary(g),
+        }
+    }
+}
+
+#[test]
+fn test_assert_roundtrip() {
+    assert_roundtrip(edges());
+}
+
+#[test]
+#[cfg(feature = "proptest")]
+fn test_assert_proptest_roundtrip() {
+    redb_model::testing::proptest::assert_roundtrip::<Edge>();
+}
+
+#[test]
+#[cfg(feature = "quickcheck")]
+fn test_assert_quickcheck_roundtrip() {
+    redb_model::testing::quickcheck::assert_roundtrip::<Edge>();
+}
+
+#[test]
+#[should_panic(expected = "`from_values` returned a different model")]
+fn test_assert_roundtrip_after_load() {
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext, hooks)]
+    struct Counter {
+        #[entry(position = "key")]
+        id: u32,
+        #[entry(position = "value")]
+        loads: u32,
+    }
+
+    impl ModelHooks for Counter {
+        fn after_load(&mut self) {
+            self.loads += 1;
+        }
+    }
+
+    assert_roundtrip([Counter { id: 0, loads: 0 }]);
+}
+
+#[test]
+#[cfg(feature = "proptest")]
+#[should_panic(expected = "minimal failing input: Counter {\n    id: 0,\n    loads: 0,\n}")]
+fn test_assert_proptest_roundtrip_after_load() {
+    use proptest::prelude::*;
+
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext, hooks)]
+    struct Counter {
+        #[entry(position = "key")]
+        id: u32,
+        #[entry(position = "value")]
+        loads: u32,
+    }
+
+    impl ModelHooks for Counter {
+        fn after_load(&mut self) {
+            self.loads += 1;
+        }
+    }
+
+    impl Arbitrary for Counter {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            (any::<u32>(), any::<u32>())
+                .prop_map(|(id, loads)| Counter { id, loads })
+                .boxed()
+        }
+    }
+
+    redb_model::testing::proptest::assert_roundtrip::<Counter>();
+}
@@ -0,0 +1,272 @@
+//! Export and import tests.
+mod common;
+
+use redb::Database;
+use redb_model::{Csv, CsvModel, Error, Model, ModelTable, ReadableModelTable};
+
+use common::{user, User};
+
+fn database(users: &[User]) -> Database {
+    let db = common::database();
+
+    let txn = db.begin_write().unwrap();
+    {
+        let mut table = txn.open_table(User::DEFINITION).unwrap();
+        for user in users {
+            table.insert_model(&mut user.clone()).unwrap();
+        }
+    }
+    txn.commit().unwrap();
+    db
+}
+
+#[test]
+fn test_csv() {
+    let users = [user(0, "a"), user(1, "b, \"c\"\nd")];
+    let db = database(&users);
+
+    let mut buf = Vec::new();
+    let txn = db.begin_read().unwrap();
+    assert_eq!(Csv::new().export::<User>(&txn, &mut buf).unwrap(), 2);
+    assert_eq!(
+        String::from_utf8(buf.clone()).unwrap(),
+        "id,name\n0,a\n1,\"b, \"\"c\"\"\nd\"\n"
+    );
+
+    let db = database(&[]);
+    let txn = db.begin_write().unwrap();
+    assert_eq!(Csv::new().import::<User>(&txn, buf.as_slice()).unwrap(), 2);
+    txn.commit().unwrap();
+
+    let txn = db.begin_read().unwrap();
+    let table = txn.open_table(User::DEFINITION).unwrap();
+    let imported = table
+        .iter_models::<User>()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(imported, users);
+}
+
+#[test]
+fn test_csv_markers() {
+    let db = database(&[user(0, "a")]);
+
+    let mut buf = Vec::new();
+    let txn = db.begin_read().unwrap();
+    Csv::new()
+        .delimiter(';')
+        .markers(true)
+        .export::<User>(&txn, &mut buf)
+        .unwrap();
+    assert_eq!(String::from_utf8(buf).unwrap(), "key:id;value:name\n0;a\n");
+
+    // Columns are imported by name, in any order.
+    let csv = "value:name;key:id\r\nb;1\r\n";
+    let db = database(&[]);
+    let txn = db.begin_write().unwrap();
+    Csv::new()
+        .delimiter(';')
+        .import::<User>(&txn, csv.as_bytes())
+        .unwrap();
+    let table = txn.open_table(User::DEFINITION).unwrap();
+    assert_eq!(table.get_model::<User>(1).unwrap(), Some(user(1, "b")));
+}
+
+#[test]
+fn test_csv_empty_field() {
+    #[derive(Model, PartialEq, Eq, Debug, Clone)]
+    #[model(impl_ext, csv)]
+    struct Tag {
+        #[entry(position = "key", redb_type = "&str")]
+        name: String,
+    }
+
+    let tags = [
+        Tag {
+            name: String::new(),
+        },
+        Tag {
+            name: String::from("a"),
+        },
+    ];
+    let db = common::database();
+    let txn = db.begin_write().unwrap();
+    {
+        let mut table = txn.open_table(Tag::DEFINITION).unwrap();
+        for tag in &tags {
+            table.insert_model(&mut tag.clone()).unwrap();
+        }
+    }
+    txn.commit().unwrap();
+
+    let mut buf = Vec::new();
+    let txn = db.begin_read().unwrap();
+    assert_eq!(Csv::new().export::<Tag>(&txn, &mut buf).unwrap(), 2);
+    assert_eq!(String::from_utf8(buf.clone()).unwrap(), "name\n\"\"\na\n");
+
+    let txn = db.begin_write().unwrap();
+    txn.delete_table(Tag::DEFINITION).unwrap();
+    assert_eq!(Csv::new().import::<Tag>(&txn, buf.as_slice()).unwrap(), 2);
+    let table = txn.open_table(Tag::DEFINITION).unwrap();
+    let imported = table
+        .iter_models::<Tag>()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(imported, tags);
+}
+
+#[test]
+fn test_csv_errors() {
+    assert_eq!(
+        User::FIELDS
+            .iter()
+            .map(|f| (f.name, f.key))
+            .collect::<Vec<_>>(),
+        [("id", true), ("name", false)]
+    );
+
+    let db = database(&[]);
+    let import = |csv: &str| {
+        let txn = db.begin_write().unwrap();
+        Csv::new().import::<User>(&txn, csv.as_bytes())
+    };
+
+    assert!(matches!(
+        import("id,value:id\n"),
+        Err(Error::Csv { line: 1, .. })
+    ));
+    assert!(matches!(
+        import("id,name\n0,a\n1\n"),
+        Err(Error::Csv { line: 3, .. })
+    ));
+
+    // Blank lines are skipped, counting towards the line numbers.
+    assert_eq!(import("id,name\n\n0,a\r\n\n").unwrap(), 1);
+    assert!(matches!(
+        import("id,name\n\n1\n"),
+        Err(Error::Csv { line: 3, .. })
+    ));
+    assert!(matches!(
+        import("name,id\n\"a\nb\",x\n"),
+        Err(Error::Csv { line: 2, message }) if message.starts_with("invalid field `id`")
+    ));
+    assert!(matches!(
+        import("id,name\n0,\"a\n"),
+        Err(Error::Csv { line: 2, .. })
+    ));
+}
+
+#[cfg(feature = "serde")]
+mod json {
+    use std::cell::RefCell;
+
+    use redb::ReadableTableMetadata;
+    use redb_model::{Json, JsonFormat};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::*;
+
+    impl Serialize for User {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            (self.id, &self.name).serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for User {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let (id, name) = Deserialize::deserialize(deserializer)?;
+            Ok(User { id, name })
+        }
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let users = [user(0, "a"), user(1, "b"), user(2, "c")];
+        let db = database(&users);
+
+        let mut buf = Vec::new();
+        let txn = db.begin_read().unwrap();
+        assert_eq!(redb_model::export::<User>(&txn, &mut buf).unwrap(), 3);
+        assert_eq!(
+            String::from_utf8(buf.clone()).unwrap(),
+            "[0,\"a\"]\n[1,\"b\"]\n[2,\"c\"]\n"
+        );
+
+        let db = database(&[]);
+        let txn = db.begin_write().unwrap();
+        assert_eq!(redb_model::import::<User>(&txn, buf.as_slice()).unwrap(), 3);
+        txn.commit().unwrap();
+
+        let txn = db.begin_read().unwrap();
+        let table = txn.open_table(User::DEFINITION).unwrap();
+        let imported = table
+            .iter_models::<User>()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(imported, users);
+    }
+
+    #[test]
+    fn test_json_array() {
+        let users = [user(0, "a"), user(1, "b")];
+        let db = database(&users);
+
+        let mut buf = Vec::new();
+        let txn = db.begin_read().unwrap();
+        Json::new()
+            .format(JsonFormat::Array)
+            .export::<User>(&txn, &mut buf)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(buf.clone()).unwrap(),
+            "[\n[0,\"a\"],\n[1,\"b\"]\n]\n"
+        );
+
+        let db = database(&[]);
+        let txn = db.begin_write().unwrap();
+        Json::new()
+            .format(JsonFormat::Array)
+            .import::<User>(&txn, buf.as_slice())
+            .unwrap();
+        txn.commit().unwrap();
+
+        let txn = db.begin_read().unwrap();
+        let table = txn.open_table(User::DEFINITION).unwrap();
+        assert_eq!(table.len().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_json_batches() {
+        let users = (0..5).map(|id| user(id, "user")).collect::<Vec<_>>();
+        let db = database(&users);
+
+        let exported = RefCell::new(Vec::new());
+        let mut buf = Vec::new();
+        let txn = db.begin_read().unwrap();
+        Json::new()
+            .batch_size(2)
+            .progress(|n| exported.borrow_mut().push(n))
+            .export::<User>(&txn, &mut buf)
+            .unwrap();
+        assert_eq!(exported.into_inner(), [2, 4, 5]);
+
+        // The last line is invalid, retaining the batches committed before it.
+        buf.extend_from_slice(b"[5]\n");
+
+        let imported = RefCell::new(Vec::new());
+        let db = database(&[]);
+        let result = Json::new()
+            .batch_size(2)
+            .progress(|n| imported.borrow_mut().push(n))
+            .import_batched::<User>(&db, buf.as_slice());
+        assert!(matches!(result, Err(Error::Json(_))));
+        assert_eq!(imported.into_inner(), [2, 4]);
+
+        let txn = db.begin_read().unwrap();
+        let table = txn.open_table(User::DEFINITION).unwrap();
+        assert_eq!(table.len().unwrap(), 4);
+    }
+}
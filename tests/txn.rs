@@ -0,0 +1,189 @@
+//! Transaction tests.
+mod common;
+
+use redb::backends::InMemoryBackend;
+use redb_model::{
+    Change, DatabaseExt, Model, ModelDatabase, ReadTxn, ReadableModelTable, Subscribers, WriteTxn,
+};
+
+use common::{user, User, UserPatch};
+
+#[test]
+fn test_write_txn() {
+    let db = common::database();
+
+    let txn = WriteTxn::new(db.begin_write().unwrap());
+    assert_eq!(txn.insert(&mut user(0, "a")).unwrap(), None);
+    assert_eq!(txn.insert(&mut user(0, "b")).unwrap(), Some(user(0, "a")));
+    assert_eq!(txn.update(&mut user(0, "c")).unwrap(), user(0, "b"));
+    txn.insert(&mut user(1, "x")).unwrap();
+    let patch = UserPatch {
+        name: Some(String::from("d")),
+    };
+    assert_eq!(txn.patch::<User>(1, patch).unwrap(), user(1, "d"));
+    assert_eq!(txn.get::<User>(0).unwrap(), Some(user(0, "c")));
+    assert_eq!(txn.range::<User, u32>(1..).unwrap(), vec![user(1, "d")]);
+    txn.commit().unwrap();
+
+    let txn = db.begin_read().unwrap();
+    let table = txn.open_table(User::DEFINITION).unwrap();
+    assert_eq!(table.get_model::<User>(0).unwrap(), Some(user(0, "c")));
+
+    let txn = WriteTxn::new(db.begin_write().unwrap());
+    assert_eq!(txn.remove::<User>(0).unwrap(), Some(user(0, "c")));
+    assert_eq!(txn.remove::<User>(0).unwrap(), None);
+    txn.commit().unwrap();
+}
+
+#[test]
+fn test_write_txn_tables() {
+    #[derive(Model, PartialEq, Eq, Debug, Clone)]
+    #[model(impl_ext)]
+    struct Follow {
+        #[entry(position = "key")]
+        source: u32,
+        #[entry(position = "key")]
+        target: u32,
+    }
+
+    let db = common::database();
+
+    let txn = WriteTxn::new(db.begin_write().unwrap());
+    for id in 0..3 {
+        txn.insert(&mut user(id, "a")).unwrap();
+        txn.insert(&mut Follow {
+            source: id,
+            target: (id + 1) % 3,
+        })
+        .unwrap();
+    }
+    assert_eq!(txn.get::<User>(2).unwrap(), Some(user(2, "a")));
+    assert!(matches!(
+        txn.open_table(User::DEFINITION),
+        Err(redb::TableError::TableAlreadyOpen(..))
+    ));
+    txn.commit().unwrap();
+
+    let txn = WriteTxn::new(db.begin_write().unwrap());
+    txn.remove::<Follow>((0, 1)).unwrap();
+    txn.abort().unwrap();
+
+    let txn = ReadTxn::new(db.begin_read().unwrap());
+    assert_eq!(txn.iter::<User>().unwrap().count(), 3);
+    assert_eq!(txn.iter::<Follow>().unwrap().count(), 3);
+}
+
+#[test]
+fn test_read_txn() {
+    #[derive(Model, PartialEq, Eq, Debug, Clone)]
+    #[model(impl_ext)]
+    struct Follow {
+        #[entry(position = "key")]
+        source: u32,
+        #[entry(position = "key")]
+        target: u32,
+    }
+
+    let db = common::database();
+
+    let txn = WriteTxn::new(db.begin_write().unwrap());
+    txn.insert(&mut user(0, "a")).unwrap();
+    txn.insert(&mut user(1, "b")).unwrap();
+    txn.insert(&mut Follow {
+        source: 0,
+        target: 1,
+    })
+    .unwrap();
+    txn.insert(&mut Follow {
+        source: 1,
+        target: 0,
+    })
+    .unwrap();
+    txn.commit().unwrap();
+
+    let txn = ReadTxn::new(db.begin_read().unwrap());
+    let followers = txn
+        .range::<Follow, (u32, u32)>((0, 0)..(1, 0))
+        .unwrap()
+        .map(|follow| txn.get::<User>(follow.unwrap().target))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(followers, vec![Some(user(1, "b"))]);
+
+    let users = txn.iter::<User>().unwrap();
+    drop(txn);
+    assert_eq!(
+        users.collect::<Result<Vec<_>, _>>().unwrap(),
+        vec![user(0, "a"), user(1, "b")]
+    );
+}
+
+#[test]
+fn test_subscribe() {
+    #[derive(Model, PartialEq, Eq, Debug, Clone)]
+    #[model(impl_ext)]
+    struct Post {
+        #[entry(position = "key")]
+        id: u32,
+        #[entry(position = "value")]
+        likes: u32,
+    }
+
+    let db = common::database();
+    let subscribers = Subscribers::new();
+    let receiver = subscribers.subscribe::<Post>();
+
+    let txn = WriteTxn::with_subscribers(db.begin_write().unwrap(), &subscribers);
+    txn.insert(&mut Post { id: 0, likes: 0 }).unwrap();
+    txn.abort().unwrap();
+    assert!(receiver.try_recv().is_err());
+
+    let txn = WriteTxn::new(db.begin_write().unwrap());
+    txn.insert(&mut Post { id: 1, likes: 0 }).unwrap();
+    txn.commit().unwrap();
+    assert!(receiver.try_recv().is_err());
+
+    let txn = WriteTxn::with_subscribers(db.begin_write().unwrap(), &subscribers);
+    txn.insert(&mut Post { id: 0, likes: 0 }).unwrap();
+    txn.update(&mut Post { id: 0, likes: 1 }).unwrap();
+    txn.remove::<Post>(0).unwrap();
+    assert!(receiver.try_recv().is_err());
+    txn.commit().unwrap();
+
+    assert_eq!(
+        receiver.try_iter().collect::<Vec<_>>(),
+        [
+            Change::Insert(Post { id: 0, likes: 0 }),
+            Change::Update {
+                previous: Post { id: 0, likes: 0 },
+                model: Post { id: 0, likes: 1 },
+            },
+            Change::Remove(Post { id: 0, likes: 1 }),
+        ]
+    );
+}
+
+#[test]
+fn test_subscribe_database() {
+    let database = || {
+        ModelDatabase::builder()
+            .register::<User>()
+            .create_with_backend(InMemoryBackend::new())
+            .unwrap()
+    };
+    let (db1, db2) = (database(), database());
+    let receiver1 = db1.subscribe::<User>();
+    let receiver2 = db2.subscribe::<User>();
+
+    db1.write(|txn| txn.insert(&mut user(0, "a"))).unwrap();
+    db2.write(|txn| txn.insert(&mut user(1, "b"))).unwrap();
+
+    assert_eq!(
+        receiver1.try_iter().collect::<Vec<_>>(),
+        [Change::Insert(user(0, "a"))]
+    );
+    assert_eq!(
+        receiver2.try_iter().collect::<Vec<_>>(),
+        [Change::Insert(user(1, "b"))]
+    );
+}
@@ -261,6 +261,70 @@ fn test_composite_borrow_type() {
     test_as_key_and_value!(db, CompositeValue, (k, v));
 }
 
+#[test]
+fn test_inferred_borrow_type() {
+    use std::{borrow::Cow, path::PathBuf, rc::Rc, sync::Arc};
+
+    let db = Database::builder()
+        .create_with_backend(InMemoryBackend::new())
+        .unwrap();
+
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext, impl_from, infer_borrowed)]
+    struct InferredValue {
+        #[entry(position = "key")]
+        key: String,
+        #[entry(position = "value")]
+        value0: Vec<u8>,
+        #[entry(position = "value")]
+        value1: Box<str>,
+        #[entry(position = "value")]
+        value2: Rc<str>,
+        #[entry(position = "value")]
+        value3: Arc<[u8]>,
+        #[entry(position = "value")]
+        value4: Cow<'static, str>,
+        #[entry(position = "value")]
+        value5: PathBuf,
+    }
+
+    let k = "key";
+    let v = (
+        b"value0".as_slice(),
+        "value1",
+        "value2",
+        b"value3".as_slice(),
+        "value4",
+        b"value5".as_slice(),
+    );
+
+    test_from_values!(InferredValue, (k, v));
+    test_from_guards!(db, InferredValue, (k, v));
+    test_from_key_and_guard!(db, InferredValue, (k, v));
+    test_as_key_and_value!(db, InferredValue, (k, v));
+    test_as_key!(InferredValue, (k, v));
+    test_as_value!(InferredValue, (k, v));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_inferred_non_utf8_path() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::PathBuf};
+
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext, infer_borrowed)]
+    struct PathValue {
+        #[entry(position = "key")]
+        key: PathBuf,
+    }
+
+    let model = PathValue {
+        key: PathBuf::from(OsStr::from_bytes(&[0xff, 0xfe])),
+    };
+    assert_eq!(model.as_key(), [0xff, 0xfe].as_slice());
+    assert_eq!(PathValue::from_values((model.as_key(), ())), model);
+}
+
 #[derive(PartialEq, Eq, Debug, Clone)]
 struct TestWrapper(u32);
 
@@ -408,3 +472,56 @@ fn test_composite_wrapper_impl_type() {
     test_from_key_and_guard!(db, CompositeValue, (k, v));
     test_as_key_and_value!(db, CompositeValue, (k, v));
 }
+
+#[test]
+fn test_nested_copy_type() {
+    let db = Database::builder()
+        .create_with_backend(InMemoryBackend::new())
+        .unwrap();
+
+    #[derive(Model, PartialEq, Eq, Debug)]
+    #[model(impl_ext, impl_from)]
+    struct NestedValue {
+        #[entry(position(key))]
+        key0: u32,
+        #[entry(position(key))]
+        key1: u32,
+        #[entry(position(value))]
+        value0: u8,
+        #[entry(position(value))]
+        value1: u8,
+        #[entry(position(value))]
+        value2: u8,
+        #[entry(position(value))]
+        value3: u8,
+        #[entry(position(value))]
+        value4: u8,
+        #[entry(position(value))]
+        value5: u8,
+        #[entry(position(value))]
+        value6: u8,
+        #[entry(position(value))]
+        value7: u8,
+        #[entry(position(value))]
+        value8: u8,
+        #[entry(position(value))]
+        value9: u8,
+        #[entry(position(value))]
+        value10: u8,
+        #[entry(position(value))]
+        value11: u8,
+        #[entry(position(value))]
+        value12: u8,
+        #[entry(position(value))]
+        value13: u8,
+    }
+
+    let (k, v) = ((0, 1), ((2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13), (14, 15)));
+
+    test_from_values!(NestedValue, (k, v));
+    test_from_guards!(db, NestedValue, (k, v));
+    test_from_key_and_guard!(db, NestedValue, (k, v));
+    test_as_key_and_value!(db, NestedValue, (k, v));
+    test_as_key!(NestedValue, (k, v));
+    test_as_value!(NestedValue, (k, v));
+}
@@ -0,0 +1,13 @@
+use redb_model::Model;
+
+#[derive(Model)]
+struct Edge {
+    #[entry(position = "key", order = 0)]
+    source: u32,
+    #[entry(position = "key", order = 2)]
+    target: u32,
+    #[entry(position = "value")]
+    label: u32,
+}
+
+fn main() {}